@@ -0,0 +1,274 @@
+//! TOGAF catalogs and matrices, the tabular artefacts of architecture deliverables.
+//!
+//! A catalog lists the components of some kinds, with a column for each selected relation kind
+//! naming the related components. A matrix crosses the components of one kind with those of
+//! another, naming the selected relations between them. Besides the [standard] artefacts,
+//! custom ones are defined in a JSON file, as an array of objects such as:
+//!
+//! ```json
+//! { "matrix": {
+//!     "name": "Application/Function matrix",
+//!     "rows": "LogicalApplicationComponent",
+//!     "columns": "InformationSystemService",
+//!     "relations": ["logicalApplicationComponentImplementsInformationSystemService"] } }
+//! ```
+//!
+//! [standard]: Artefact::standard
+
+use super::{distinct, slug, Component, ComponentKind, Relation, RelationKind};
+use crate::table::Table;
+use crate::xml::escape;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Catalog {
+    pub name: String,
+    pub kinds: Vec<ComponentKind>,
+    #[serde(default)]
+    pub relations: Vec<RelationKind>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Matrix {
+    pub name: String,
+    pub rows: ComponentKind,
+    pub columns: ComponentKind,
+    pub relations: Vec<RelationKind>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Artefact {
+    Catalog(Catalog),
+    Matrix(Matrix),
+}
+
+impl Artefact {
+    /// The Business Service/Function catalog, the Application Portfolio catalog, and the
+    /// Business Service/Information System Service matrix.
+    pub fn standard() -> Vec<Artefact> {
+        vec![
+            Artefact::Catalog(Catalog {
+                name: "Business Service/Function catalog".to_string(),
+                kinds: vec![ComponentKind::BusinessService, ComponentKind::Function],
+                relations: vec![
+                    RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+                ],
+            }),
+            Artefact::Catalog(Catalog {
+                name: "Application Portfolio catalog".to_string(),
+                kinds: vec![
+                    ComponentKind::LogicalApplicationComponent,
+                    ComponentKind::PhysicalApplicationComponent,
+                ],
+                relations: vec![
+                    RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+                    RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+                    RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent,
+                ],
+            }),
+            Artefact::Matrix(Matrix {
+                name: "Business Service/Information System Service matrix".to_string(),
+                rows: ComponentKind::BusinessService,
+                columns: ComponentKind::InformationSystemService,
+                relations: vec![RelationKind::InformationSystemServiceRealizesBusinessService],
+            }),
+        ]
+    }
+
+    pub fn from_json(content: &str) -> serde_json::Result<Vec<Artefact>> {
+        serde_json::from_str(content)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Artefact::Catalog(c) => &c.name,
+            Artefact::Matrix(m) => &m.name,
+        }
+    }
+
+    pub fn table(&self, components: &[Component], relations: &[Relation]) -> Table {
+        match self {
+            Artefact::Catalog(c) => c.table(components, relations),
+            Artefact::Matrix(m) => m.table(components, relations),
+        }
+    }
+
+    /// Renders the table as a page titled after the artefact, styled by the stylesheet at the
+    /// given path.
+    pub fn to_html(&self, table: &Table, stylesheet: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{}</title>\n<link rel=\"stylesheet\" href=\"{}\">\n</head>\n<body>\n\
+             <h1>{}</h1>\n{}</body>\n</html>\n",
+            escape(self.name()),
+            escape(stylesheet),
+            escape(self.name()),
+            table.to_html()
+        )
+    }
+}
+
+/// A distinct name for files containing each artefact, based on its name and numbered where
+/// names collide. Artefacts with an empty slug are named `artefact`.
+pub fn file_names(artefacts: &[Artefact]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    artefacts
+        .iter()
+        .map(|a| distinct(&slug(a.name()), "artefact", &mut taken))
+        .collect()
+}
+
+impl Catalog {
+    /// Lists the components by kind, each relation column naming the components at the other
+    /// end of relations of that kind, in either direction.
+    pub fn table(&self, components: &[Component], relations: &[Relation]) -> Table {
+        let mut header = vec![
+            "Name".to_string(),
+            "Kind".to_string(),
+            "Description".to_string(),
+        ];
+        header.extend(self.relations.iter().map(|k| k.label().to_string()));
+        let mut rows = vec![];
+        for kind in self.kinds.iter() {
+            for c in components.iter().filter(|c| c.kind == *kind) {
+                let mut row = vec![
+                    c.label.to_string(),
+                    c.kind.label().to_string(),
+                    c.description.clone().unwrap_or_default(),
+                ];
+                for kind in self.relations.iter() {
+                    let related = relations
+                        .iter()
+                        .filter(|r| r.kind == *kind)
+                        .filter_map(|r| {
+                            let other = if r.from == c.id {
+                                &r.to
+                            } else if r.to == c.id {
+                                &r.from
+                            } else {
+                                return None;
+                            };
+                            components.iter().find(|c| c.id == *other)
+                        })
+                        .map(|c| c.label.as_str())
+                        .collect::<Vec<_>>();
+                    row.push(related.join("; "));
+                }
+                rows.push(row);
+            }
+        }
+        Table { header, rows }
+    }
+}
+
+impl Matrix {
+    /// Crosses the components, each cell naming the kinds of the relations between its row and
+    /// column component, in either direction.
+    pub fn table(&self, components: &[Component], relations: &[Relation]) -> Table {
+        let columns = components
+            .iter()
+            .filter(|c| c.kind == self.columns)
+            .collect::<Vec<_>>();
+        let mut header = vec![format!("{} / {}", self.rows.label(), self.columns.label())];
+        header.extend(columns.iter().map(|c| c.label.to_string()));
+        let rows = components
+            .iter()
+            .filter(|c| c.kind == self.rows)
+            .map(|row| {
+                let mut cells = vec![row.label.to_string()];
+                for column in columns.iter() {
+                    let kinds = relations
+                        .iter()
+                        .filter(|r| self.relations.contains(&r.kind))
+                        .filter(|r| {
+                            (r.from == row.id && r.to == column.id)
+                                || (r.from == column.id && r.to == row.id)
+                        })
+                        .map(|r| r.kind.label())
+                        .collect::<Vec<_>>();
+                    cells.push(kinds.join("; "));
+                }
+                cells
+            })
+            .collect();
+        Table { header, rows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::catalog::{file_names, Artefact};
+    use crate::architecture::fixtures;
+
+    #[test]
+    fn generates_standard_artefacts() {
+        let components = fixtures::components();
+        let relations = fixtures::relations();
+        let standard = Artefact::standard();
+
+        let catalog = standard[0].table(&components, &relations);
+        assert_eq!(catalog.rows.len(), 4);
+        assert_eq!(
+            catalog.rows[0],
+            [
+                "knowledge service",
+                "Business service",
+                "",
+                "knowledge management"
+            ]
+        );
+        let portfolio = standard[1].table(&components, &relations);
+        assert_eq!(portfolio.rows[0][3], "knowledge application service");
+        assert_eq!(portfolio.rows[0][4], "architecture application service");
+        let matrix = standard[2].table(&components, &relations).to_markdown();
+        assert_eq!(
+            matrix,
+            "| Business service / Information system service | knowledge application service \
+             | architecture application service |\n\
+             | --- | --- | --- |\n\
+             | knowledge service | realizes |  |\n\
+             | architecture service |  | realizes |\n"
+        );
+        assert_eq!(
+            file_names(&standard)[2],
+            "business-service-information-system-service-matrix"
+        );
+        let page = standard[0].to_html(&catalog, "../main.css");
+        assert!(page.contains("<link rel=\"stylesheet\" href=\"../main.css\">"));
+        assert!(page.contains("<h1>Business Service/Function catalog</h1>\n<table>"));
+    }
+
+    #[test]
+    fn reads_custom_artefacts() {
+        let artefacts = Artefact::from_json(
+            r#"[{ "catalog": { "name": "Processes", "kinds": ["Process"] } },
+                { "matrix": {
+                    "name": "Function/Process matrix",
+                    "rows": "Function",
+                    "columns": "Process",
+                    "relations": ["functionIsRealizedByAndOrSupportsProcess"] } },
+                { "catalog": { "name": "processes", "kinds": ["Process"] } },
+                { "catalog": { "name": "?", "kinds": ["Process"] } }]"#,
+        )
+        .unwrap();
+
+        let table = artefacts[1].table(&fixtures::components(), &fixtures::relations());
+
+        assert_eq!(artefacts[0].name(), "Processes");
+        assert_eq!(
+            table.rows[1],
+            ["architecture management", "supports or is realized by"]
+        );
+        assert_eq!(
+            file_names(&artefacts),
+            [
+                "processes",
+                "function-process-matrix",
+                "processes-2",
+                "artefact"
+            ]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Coverage of the relations that the ontology's domain and range pairs lead us to expect.
+
+use super::{Component, ComponentId, ComponentKind, Relation};
+use crate::table::Table;
+use std::collections::{HashMap, HashSet};
+
+/// An ontology property, relating components of the `domain` kind to those of the `range` kind.
+#[derive(Debug)]
+pub struct Expectation {
+    pub property: String,
+    pub domain: ComponentKind,
+    pub range: ComponentKind,
+}
+
+/// A component not related to any component of a kind the ontology relates it to.
+#[derive(Debug)]
+pub struct Missing {
+    pub component: ComponentId,
+    pub label: String,
+    pub kind: ComponentKind,
+    pub related_kind: ComponentKind,
+    /// The ontology properties that could have related them.
+    pub properties: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Score {
+    pub kind: ComponentKind,
+    pub expected: usize,
+    pub satisfied: usize,
+}
+
+impl Score {
+    pub fn percentage(&self) -> f64 {
+        if self.expected == 0 {
+            100.0
+        } else {
+            100.0 * self.satisfied as f64 / self.expected as f64
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Coverage {
+    pub missing: Vec<Missing>,
+    pub scores: Vec<Score>,
+}
+
+impl Coverage {
+    /// Whether every component kind scores at least `minimum` percent, for use as a CI gate.
+    pub fn meets(&self, minimum: f64) -> bool {
+        self.scores.iter().all(|s| s.percentage() >= minimum)
+    }
+
+    pub fn scores_table(&self) -> Table {
+        Table {
+            header: vec![
+                "Kind".to_string(),
+                "Expected relations".to_string(),
+                "Present".to_string(),
+                "Score".to_string(),
+            ],
+            rows: self
+                .scores
+                .iter()
+                .map(|s| {
+                    vec![
+                        s.kind.label().to_string(),
+                        s.expected.to_string(),
+                        s.satisfied.to_string(),
+                        format!("{:.0}%", s.percentage()),
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    pub fn missing_table(&self) -> Table {
+        Table {
+            header: vec![
+                "Component".to_string(),
+                "Kind".to_string(),
+                "Missing relation to".to_string(),
+                "Properties".to_string(),
+            ],
+            rows: self
+                .missing
+                .iter()
+                .map(|m| {
+                    vec![
+                        m.label.to_string(),
+                        m.kind.label().to_string(),
+                        m.related_kind.label().to_string(),
+                        m.properties
+                            .iter()
+                            .map(|p| p.rsplit('#').next().unwrap_or(p))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Checks that each component is related, in either direction, to at least one component of
+/// every kind that some ontology property pairs its kind with. Inverse properties thereby count
+/// as one expectation. Properties relating a kind to itself, such as decompositions, are
+/// optional and not expected.
+pub fn coverage(
+    components: &[Component],
+    relations: &[Relation],
+    expectations: &[Expectation],
+) -> Coverage {
+    let mut expected: HashMap<(ComponentKind, ComponentKind), Vec<String>> = HashMap::new();
+    for e in expectations.iter().filter(|e| e.domain != e.range) {
+        for pair in [(e.domain, e.range), (e.range, e.domain)].iter() {
+            let properties = expected.entry(*pair).or_default();
+            if !properties.contains(&e.property) {
+                properties.push(e.property.to_string());
+            }
+        }
+    }
+
+    let kinds = components
+        .iter()
+        .map(|c| (&c.id, c.kind))
+        .collect::<HashMap<&ComponentId, ComponentKind>>();
+    let mut related = HashSet::new();
+    for r in relations.iter() {
+        if let (Some(from), Some(to)) = (kinds.get(&r.from), kinds.get(&r.to)) {
+            related.insert((&r.from, *to));
+            related.insert((&r.to, *from));
+        }
+    }
+
+    let mut sorted = components.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut missing = vec![];
+    let mut scores = vec![];
+    for kind in ComponentKind::ALL.iter().copied() {
+        let mut score = Score {
+            kind,
+            expected: 0,
+            satisfied: 0,
+        };
+        for related_kind in ComponentKind::ALL.iter().copied() {
+            let properties = match expected.get(&(kind, related_kind)) {
+                Some(properties) => properties,
+                None => continue,
+            };
+            for c in sorted.iter().filter(|c| c.kind == kind) {
+                score.expected += 1;
+                if related.contains(&(&c.id, related_kind)) {
+                    score.satisfied += 1;
+                } else {
+                    missing.push(Missing {
+                        component: c.id.clone(),
+                        label: c.label.to_string(),
+                        kind,
+                        related_kind,
+                        properties: properties.clone(),
+                    });
+                }
+            }
+        }
+        if score.expected > 0 {
+            scores.push(score);
+        }
+    }
+
+    Coverage { missing, scores }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::coverage::{coverage, Expectation};
+    use crate::architecture::{fixtures, ComponentKind, RelationKind};
+
+    fn expectation(kind: RelationKind, domain: ComponentKind, range: ComponentKind) -> Expectation {
+        Expectation {
+            property: kind.iri(),
+            domain,
+            range,
+        }
+    }
+
+    fn expectations() -> Vec<Expectation> {
+        vec![
+            expectation(
+                RelationKind::InformationSystemServiceRealizesBusinessService,
+                ComponentKind::InformationSystemService,
+                ComponentKind::BusinessService,
+            ),
+            expectation(
+                RelationKind::FunctionIsRealizedByAndOrSupportsProcess,
+                ComponentKind::Function,
+                ComponentKind::Process,
+            ),
+            expectation(
+                RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+                ComponentKind::LogicalApplicationComponent,
+                ComponentKind::InformationSystemService,
+            ),
+            expectation(
+                RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+                ComponentKind::InformationSystemService,
+                ComponentKind::LogicalApplicationComponent,
+            ),
+        ]
+    }
+
+    #[test]
+    fn reports_missing_relations_per_kind() {
+        let components = fixtures::components();
+        let relations = fixtures::relations()
+            .into_iter()
+            .filter(|r| {
+                r.kind
+                    != RelationKind::LogicalApplicationComponentImplementsInformationSystemService
+            })
+            .collect::<Vec<_>>();
+        let coverage = coverage(&components, &relations, &expectations());
+
+        assert_eq!(coverage.missing.len(), 1);
+        assert_eq!(coverage.missing[0].label, "knowledge application service");
+        assert_eq!(
+            coverage.missing[0].related_kind,
+            ComponentKind::LogicalApplicationComponent
+        );
+        assert_eq!(coverage.missing[0].properties.len(), 2);
+
+        let score = coverage
+            .scores
+            .iter()
+            .find(|s| s.kind == ComponentKind::InformationSystemService)
+            .unwrap();
+        assert_eq!(score.percentage(), 75.0);
+        assert!(coverage.meets(75.0));
+        assert!(!coverage.meets(80.0));
+    }
+}
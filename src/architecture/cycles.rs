@@ -0,0 +1,204 @@
+//! Detection of cycles, such as processes decomposing functions that support those processes.
+
+use super::{Component, ComponentId, Relation, RelationKind};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A component and the relation by which it leads to the next step in a cycle.
+#[derive(Debug, PartialEq)]
+pub struct Step {
+    pub component: ComponentId,
+    pub relation: RelationKind,
+}
+
+/// A strongly connected set of components, with one cycle through it.
+#[derive(Debug)]
+pub struct Cycle {
+    /// All components in the strongly connected set, in order of IRI.
+    pub components: Vec<ComponentId>,
+    /// A shortest cycle from the first component back to itself.
+    pub steps: Vec<Step>,
+}
+
+impl Cycle {
+    pub fn describe(&self, components: &[Component]) -> String {
+        let label = |id: &ComponentId| {
+            components
+                .iter()
+                .find(|c| &c.id == id)
+                .map(|c| c.label.to_string())
+                .unwrap_or_else(|| format!("<{}>", id.value))
+        };
+        let mut s = String::new();
+        for step in self.steps.iter() {
+            s.push_str(&format!(
+                "{} -[{}]-> ",
+                label(&step.component),
+                step.relation.label()
+            ));
+        }
+        s.push_str(&label(&self.components[0]));
+        s
+    }
+}
+
+/// Finds the strongly connected components of the graph of `relations` of the given `kinds`, by
+/// Tarjan's algorithm, and reports those that contain a cycle.
+pub fn cycles(relations: &[Relation], kinds: &[RelationKind]) -> Vec<Cycle> {
+    let mut graph: HashMap<&ComponentId, Vec<&Relation>> = HashMap::new();
+    for r in relations.iter().filter(|r| kinds.contains(&r.kind)) {
+        graph.entry(&r.from).or_default().push(r);
+        graph.entry(&r.to).or_default();
+    }
+    let mut nodes = graph.keys().copied().collect::<Vec<_>>();
+    nodes.sort();
+
+    let mut tarjan = Tarjan {
+        graph: &graph,
+        index: 0,
+        indices: HashMap::new(),
+        lowlinks: HashMap::new(),
+        stack: vec![],
+        on_stack: HashSet::new(),
+        components: vec![],
+    };
+    for node in nodes {
+        if !tarjan.indices.contains_key(node) {
+            tarjan.connect(node);
+        }
+    }
+
+    let mut cycles = tarjan
+        .components
+        .into_iter()
+        .filter_map(|members| {
+            let mut members = members.into_iter().collect::<Vec<_>>();
+            members.sort();
+            let steps = shortest_cycle(&graph, &members)?;
+            Some(Cycle {
+                components: members.into_iter().cloned().collect(),
+                steps,
+            })
+        })
+        .collect::<Vec<_>>();
+    cycles.sort_by(|a, b| a.components.cmp(&b.components));
+    cycles
+}
+
+struct Tarjan<'a> {
+    graph: &'a HashMap<&'a ComponentId, Vec<&'a Relation>>,
+    index: usize,
+    indices: HashMap<&'a ComponentId, usize>,
+    lowlinks: HashMap<&'a ComponentId, usize>,
+    stack: Vec<&'a ComponentId>,
+    on_stack: HashSet<&'a ComponentId>,
+    components: Vec<Vec<&'a ComponentId>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn connect(&mut self, node: &'a ComponentId) {
+        self.indices.insert(node, self.index);
+        self.lowlinks.insert(node, self.index);
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        for r in self.graph[node].iter() {
+            let next = &r.to;
+            if !self.indices.contains_key(next) {
+                self.connect(next);
+                let lowlink = self.lowlinks[node].min(self.lowlinks[next]);
+                self.lowlinks.insert(node, lowlink);
+            } else if self.on_stack.contains(next) {
+                let lowlink = self.lowlinks[node].min(self.indices[next]);
+                self.lowlinks.insert(node, lowlink);
+            }
+        }
+
+        if self.lowlinks[node] == self.indices[node] {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+/// Finds a shortest path from the first member back to itself within `members`, if any.
+fn shortest_cycle(
+    graph: &HashMap<&ComponentId, Vec<&Relation>>,
+    members: &[&ComponentId],
+) -> Option<Vec<Step>> {
+    let start = members[0];
+    let mut previous: HashMap<&ComponentId, &Relation> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(node) = queue.pop_front() {
+        for r in graph[node].iter().filter(|r| members.contains(&&r.to)) {
+            if &r.to == start {
+                let mut steps = vec![Step {
+                    component: r.from.clone(),
+                    relation: r.kind,
+                }];
+                let mut current = &r.from;
+                while current != start {
+                    let p = previous[current];
+                    steps.push(Step {
+                        component: p.from.clone(),
+                        relation: p.kind,
+                    });
+                    current = &p.from;
+                }
+                steps.reverse();
+                return Some(steps);
+            }
+            if !previous.contains_key(&r.to) {
+                previous.insert(&r.to, r);
+                queue.push_back(&r.to);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::cycles::cycles;
+    use crate::architecture::fixtures::{self, relation};
+    use crate::architecture::RelationKind;
+
+    #[test]
+    fn finds_no_cycles_in_example() {
+        assert!(cycles(&fixtures::relations(), &RelationKind::ALL).is_empty());
+    }
+
+    #[test]
+    fn reports_cycle_in_order() {
+        let mut relations = fixtures::relations();
+        relations.push(relation(
+            "knowledge-management",
+            RelationKind::FunctionIsRealizedByAndOrSupportsProcess,
+            "architecture-modelling",
+        ));
+        let components = fixtures::components();
+
+        let found = cycles(&relations, &RelationKind::ALL);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].components.len(), 2);
+        assert_eq!(
+            found[0].describe(&components),
+            "architecture modelling -[orchestrates and/or decomposes]-> knowledge management \
+             -[supports or is realized by]-> architecture modelling"
+        );
+
+        let found = cycles(
+            &relations,
+            &[RelationKind::ProcessDecomposesAndOrOrchestratesFunction],
+        );
+        assert!(found.is_empty());
+    }
+}
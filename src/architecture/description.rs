@@ -0,0 +1,360 @@
+//! Everything the dataset states about a component, with labels resolved for display.
+
+use super::{iri, ComponentId};
+use crate::table::Table;
+use rdf::node::Node;
+use std::collections::HashMap;
+use std::fmt;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+const RDFS_COMMENT: &str = "http://www.w3.org/2000/01/rdf-schema#comment";
+
+/// A literal with its language tag or datatype, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    pub value: String,
+    pub language: Option<String>,
+    pub datatype: Option<String>,
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)?;
+        match (&self.language, &self.datatype) {
+            (Some(language), _) => write!(f, " ({})", language),
+            (None, Some(datatype)) => write!(f, " ({})", datatype),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// A resource with its label, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub iri: String,
+    pub label: Option<String>,
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{}", label),
+            None => write!(f, "<{}>", self.iri),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Resource(Reference),
+    Literal(Literal),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Resource(r) => r.fmt(f),
+            Value::Literal(l) => l.fmt(f),
+        }
+    }
+}
+
+/// A statement about the component, with the value at its other end: the object of an outgoing
+/// statement or the subject of an incoming one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub property: Reference,
+    pub value: Value,
+    /// The named graphs containing the statement.
+    pub graphs: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ComponentDescription {
+    pub id: ComponentId,
+    pub labels: Vec<Literal>,
+    pub comments: Vec<Literal>,
+    pub types: Vec<Reference>,
+    pub outgoing: Vec<Property>,
+    pub incoming: Vec<Property>,
+    /// The named graphs containing any of the statements.
+    pub graphs: Vec<String>,
+}
+
+impl ComponentDescription {
+    /// Lists the outgoing and then the incoming properties.
+    pub fn table(&self) -> Table {
+        let rows = self
+            .outgoing
+            .iter()
+            .map(|p| ("outgoing", p))
+            .chain(self.incoming.iter().map(|p| ("incoming", p)))
+            .map(|(direction, p)| {
+                vec![
+                    direction.to_string(),
+                    p.property.to_string(),
+                    p.value.to_string(),
+                    p.graphs.join("; "),
+                ]
+            })
+            .collect();
+        Table {
+            header: vec![
+                "Direction".to_string(),
+                "Property".to_string(),
+                "Value".to_string(),
+                "Graph".to_string(),
+            ],
+            rows,
+        }
+    }
+}
+
+/// Reads the description from the bindings of a `description.sparql` query. Statements with
+/// multiple labels for the property or value are bound once for each, of which the first is
+/// used. Statements in multiple named graphs are bound once for each graph.
+pub(super) fn from_bindings(
+    id: &ComponentId,
+    bindings: &[HashMap<String, Node>],
+) -> ComponentDescription {
+    let mut description = ComponentDescription {
+        id: id.clone(),
+        labels: vec![],
+        comments: vec![],
+        types: vec![],
+        outgoing: vec![],
+        incoming: vec![],
+        graphs: vec![],
+    };
+    for record in bindings.iter() {
+        let property = Property {
+            property: Reference {
+                iri: iri(record.get("property")),
+                label: literal(record.get("propertyLabel")).map(|l| l.value),
+            },
+            value: match record.get("value") {
+                Some(Node::UriNode { uri }) => Value::Resource(Reference {
+                    iri: uri.to_string().to_string(),
+                    label: literal(record.get("valueLabel")).map(|l| l.value),
+                }),
+                v => {
+                    Value::Literal(literal(v).unwrap_or_else(|| panic!("Unexpected value {:?}", v)))
+                }
+            },
+            graphs: vec![iri(record.get("graph"))],
+        };
+        let properties = match literal(record.get("direction")) {
+            Some(d) if d.value == "outgoing" => &mut description.outgoing,
+            Some(d) if d.value == "incoming" => &mut description.incoming,
+            d => panic!("Unexpected direction {:?}", d),
+        };
+        for graph in property.graphs.iter() {
+            if !description.graphs.contains(graph) {
+                description.graphs.push(graph.to_string());
+            }
+        }
+        match properties
+            .iter_mut()
+            .find(|p| p.property.iri == property.property.iri && same(&p.value, &property.value))
+        {
+            Some(p) => {
+                for graph in property.graphs {
+                    if !p.graphs.contains(&graph) {
+                        p.graphs.push(graph);
+                    }
+                }
+            }
+            None => properties.push(property),
+        }
+    }
+    for p in description.outgoing.iter() {
+        match (p.property.iri.as_str(), &p.value) {
+            (RDFS_LABEL, Value::Literal(l)) => description.labels.push(l.clone()),
+            (RDFS_COMMENT, Value::Literal(l)) => description.comments.push(l.clone()),
+            (RDF_TYPE, Value::Resource(r)) => description.types.push(r.clone()),
+            _ => {}
+        }
+    }
+    description
+}
+
+/// Whether the values are the same node, regardless of labels.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Resource(a), Value::Resource(b)) => a.iri == b.iri,
+        (Value::Literal(a), Value::Literal(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn literal(node: Option<&Node>) -> Option<Literal> {
+    match node {
+        Some(Node::LiteralNode {
+            literal,
+            data_type,
+            language,
+        }) => Some(Literal {
+            value: literal.to_string(),
+            language: language.clone(),
+            datatype: data_type.as_ref().map(|d| d.to_string().to_string()),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::description::{from_bindings, Literal, Reference, Value};
+    use crate::architecture::fixtures;
+    use rdf::node::Node;
+    use rdf::uri::Uri;
+    use std::collections::HashMap;
+
+    fn uri(value: &str) -> Node {
+        Node::UriNode {
+            uri: Uri::new(value.to_string()),
+        }
+    }
+
+    fn literal(value: &str, language: Option<&str>) -> Node {
+        Node::LiteralNode {
+            literal: value.to_string(),
+            data_type: None,
+            language: language.map(|l| l.to_string()),
+        }
+    }
+
+    fn record(
+        graph: &str,
+        direction: &str,
+        property: &str,
+        property_label: Option<&str>,
+        value: Node,
+        value_label: Option<&str>,
+    ) -> HashMap<String, Node> {
+        let mut record = HashMap::new();
+        record.insert("direction".to_string(), literal(direction, None));
+        record.insert("graph".to_string(), uri(graph));
+        record.insert("property".to_string(), uri(property));
+        record.insert("value".to_string(), value);
+        if let Some(label) = property_label {
+            record.insert("propertyLabel".to_string(), literal(label, Some("en")));
+        }
+        if let Some(label) = value_label {
+            record.insert("valueLabel".to_string(), literal(label, None));
+        }
+        record
+    }
+
+    #[test]
+    fn reads_typed_description() {
+        let id = fixtures::id("knowledge-service");
+        let realizes = "http://example.com/realizes";
+        let bindings = vec![
+            record(
+                fixtures::GRAPH,
+                "outgoing",
+                "http://www.w3.org/2000/01/rdf-schema#label",
+                None,
+                literal("kennisdienst", Some("nl")),
+                None,
+            ),
+            record(
+                fixtures::GRAPH,
+                "outgoing",
+                "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+                None,
+                uri("http://example.com/BusinessService"),
+                Some("Business Service"),
+            ),
+            record(
+                fixtures::GRAPH,
+                "incoming",
+                realizes,
+                Some("realizes"),
+                uri(&fixtures::id("knowledge-application-service").value),
+                Some("knowledge application service"),
+            ),
+            record(
+                fixtures::GRAPH,
+                "incoming",
+                realizes,
+                Some("realises"),
+                uri(&fixtures::id("knowledge-application-service").value),
+                Some("knowledge application service"),
+            ),
+        ];
+
+        let description = from_bindings(&id, &bindings);
+
+        assert_eq!(
+            description.labels,
+            [Literal {
+                value: "kennisdienst".to_string(),
+                language: Some("nl".to_string()),
+                datatype: None,
+            }]
+        );
+        assert_eq!(
+            description.types,
+            [Reference {
+                iri: "http://example.com/BusinessService".to_string(),
+                label: Some("Business Service".to_string()),
+            }]
+        );
+        assert_eq!(description.outgoing.len(), 2);
+        assert_eq!(description.incoming.len(), 1);
+        assert_eq!(
+            description.incoming[0].property.label.as_deref(),
+            Some("realizes")
+        );
+        assert!(matches!(description.incoming[0].value, Value::Resource(_)));
+        assert_eq!(description.graphs, [fixtures::GRAPH]);
+        assert_eq!(
+            description.table().rows[0],
+            [
+                "outgoing",
+                "<http://www.w3.org/2000/01/rdf-schema#label>",
+                "kennisdienst (nl)",
+                fixtures::GRAPH
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_graphs_of_each_statement() {
+        let id = fixtures::id("knowledge-service");
+        let archimate = "http://localhost:3030/architecture/data/archimate.ttl";
+        let label = "http://www.w3.org/2000/01/rdf-schema#label";
+        let bindings = vec![
+            record(
+                fixtures::GRAPH,
+                "outgoing",
+                label,
+                None,
+                literal("knowledge service", None),
+                None,
+            ),
+            record(
+                archimate,
+                "outgoing",
+                label,
+                None,
+                literal("knowledge service", None),
+                None,
+            ),
+        ];
+
+        let description = from_bindings(&id, &bindings);
+
+        assert_eq!(description.outgoing.len(), 1);
+        assert_eq!(description.outgoing[0].graphs, [fixtures::GRAPH, archimate]);
+        assert_eq!(description.labels.len(), 1);
+        assert_eq!(description.graphs, [fixtures::GRAPH, archimate]);
+        assert_eq!(
+            description.table().rows[0][3],
+            format!("{}; {}", fixtures::GRAPH, archimate)
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Differences between a baseline and a target architecture, rendered as one merged diagram.
+
+use super::theme::{EdgeStyle, NodeStyle};
+use super::visualization::{visualize, Options, Visualization};
+use super::{Component, ComponentId, Relation};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Unchanged,
+    Added,
+    Removed,
+    /// Changed in the described ways, such as in label or kind.
+    Changed(Vec<String>),
+}
+
+/// All components and relations of the baseline and the target, each with its change. Changed
+/// components are as in the target.
+#[derive(Debug)]
+pub struct Diff {
+    pub components: Vec<(Component, Change)>,
+    pub relations: Vec<(Relation, Change)>,
+}
+
+pub fn diff(
+    baseline_components: &[Component],
+    baseline_relations: &[Relation],
+    target_components: &[Component],
+    target_relations: &[Relation],
+) -> Diff {
+    let baseline = baseline_components
+        .iter()
+        .map(|c| (&c.id, c))
+        .collect::<HashMap<_, _>>();
+    let target = target_components
+        .iter()
+        .map(|c| &c.id)
+        .collect::<HashSet<_>>();
+    let mut components = target_components
+        .iter()
+        .map(|c| {
+            let change = match baseline.get(&c.id) {
+                None => Change::Added,
+                Some(b) => {
+                    let changes = changes(b, c);
+                    if changes.is_empty() {
+                        Change::Unchanged
+                    } else {
+                        Change::Changed(changes)
+                    }
+                }
+            };
+            (c.clone(), change)
+        })
+        .collect::<Vec<_>>();
+    components.extend(
+        baseline_components
+            .iter()
+            .filter(|c| !target.contains(&c.id))
+            .map(|c| (c.clone(), Change::Removed)),
+    );
+
+    let baseline = baseline_relations.iter().collect::<HashSet<_>>();
+    let target = target_relations.iter().collect::<HashSet<_>>();
+    let mut relations = target_relations
+        .iter()
+        .map(|r| {
+            let change = if baseline.contains(r) {
+                Change::Unchanged
+            } else {
+                Change::Added
+            };
+            (r.clone(), change)
+        })
+        .collect::<Vec<_>>();
+    relations.extend(
+        baseline_relations
+            .iter()
+            .filter(|r| !target.contains(r))
+            .map(|r| (r.clone(), Change::Removed)),
+    );
+
+    Diff {
+        components,
+        relations,
+    }
+}
+
+fn changes(baseline: &Component, target: &Component) -> Vec<String> {
+    let mut changes = vec![];
+    if baseline.label != target.label {
+        changes.push(format!(
+            "label \"{}\" became \"{}\"",
+            baseline.label, target.label
+        ));
+    }
+    if baseline.kind != target.kind {
+        changes.push(format!(
+            "kind {} became {}",
+            baseline.kind.label(),
+            target.kind.label()
+        ));
+    }
+    if baseline.description != target.description {
+        changes.push("description changed".to_string());
+    }
+    changes
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.components
+            .iter()
+            .all(|(_, change)| *change == Change::Unchanged)
+            && self
+                .relations
+                .iter()
+                .all(|(_, change)| *change == Change::Unchanged)
+    }
+
+    /// One line per change, marked with `+` if added, `-` if removed or `~` if changed.
+    pub fn summary(&self) -> String {
+        let labels = self
+            .components
+            .iter()
+            .map(|(c, _)| (&c.id, c.label.as_str()))
+            .collect::<HashMap<_, _>>();
+        let label = |id: &ComponentId| {
+            labels
+                .get(id)
+                .map_or_else(|| format!("<{}>", id.value), |l| format!("\"{}\"", l))
+        };
+        let mut lines = vec![];
+        for (c, change) in self.components.iter() {
+            let component = format!("{} \"{}\"", c.kind.label(), c.label);
+            match change {
+                Change::Unchanged => {}
+                Change::Added => lines.push(format!("+ {}", component)),
+                Change::Removed => lines.push(format!("- {}", component)),
+                Change::Changed(changes) => {
+                    lines.push(format!("~ {}: {}", component, changes.join("; ")))
+                }
+            }
+        }
+        for (r, change) in self.relations.iter() {
+            let relation = format!("{} {} {}", label(&r.from), r.kind.label(), label(&r.to));
+            match change {
+                Change::Added => lines.push(format!("+ {}", relation)),
+                Change::Removed => lines.push(format!("- {}", relation)),
+                _ => {}
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Renders everything in the baseline and the target, with added elements green, removed
+    /// elements red and dashed, and changed components orange.
+    pub fn visualization(&self, options: &Options) -> Visualization {
+        let mut options = options.clone();
+        for (c, change) in self.components.iter() {
+            if let Some(color) = color(change) {
+                let mut style = NodeStyle {
+                    color: Some(color.to_string()),
+                    pen_width: Some("2".to_string()),
+                    ..NodeStyle::default()
+                };
+                if *change == Change::Removed {
+                    let base = options.theme.node_style(c.kind).style;
+                    let base = base.map_or_else(String::new, |s| format!("{},", s));
+                    style.style = Some(format!("{}dashed", base));
+                }
+                options.node_styles.insert(c.id.clone(), style);
+            }
+        }
+        for (r, change) in self.relations.iter() {
+            if let Some(color) = color(change) {
+                let style = EdgeStyle {
+                    color: Some(color.to_string()),
+                    font_color: Some(color.to_string()),
+                    pen_width: Some("2".to_string()),
+                    style: match change {
+                        Change::Removed => Some("dashed".to_string()),
+                        _ => None,
+                    },
+                    ..EdgeStyle::default()
+                };
+                options.edge_styles.insert(r.clone(), style);
+            }
+        }
+        let components = self
+            .components
+            .iter()
+            .map(|(c, _)| c.clone())
+            .collect::<Vec<_>>();
+        let relations = self
+            .relations
+            .iter()
+            .map(|(r, _)| r.clone())
+            .collect::<Vec<_>>();
+        visualize(&components, &relations, &options)
+    }
+}
+
+fn color(change: &Change) -> Option<&'static str> {
+    match change {
+        Change::Unchanged => None,
+        Change::Added => Some("#1a9850"),
+        Change::Removed => Some("#d73027"),
+        Change::Changed(_) => Some("#f46d43"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::diff::{diff, Change};
+    use crate::architecture::fixtures::{self, component, relation};
+    use crate::architecture::visualization::Options;
+    use crate::architecture::{ComponentKind, RelationKind};
+
+    #[test]
+    fn marks_added_removed_and_changed() {
+        let baseline = fixtures::components();
+        let baseline_relations = fixtures::relations();
+        let mut target = baseline[1..].to_vec();
+        target[0].label = "knowledge provision".to_string();
+        target.push(component(
+            "knowledge-store",
+            "knowledge store",
+            ComponentKind::DataEntity,
+        ));
+        let mut target_relations = baseline_relations[1..].to_vec();
+        target_relations.push(relation(
+            "knowledge-service",
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+            "architecture-management",
+        ));
+
+        let d = diff(&baseline, &baseline_relations, &target, &target_relations);
+
+        assert_eq!(d.components.len(), baseline.len() + 1);
+        assert_eq!(
+            d.summary(),
+            "~ Business service \"knowledge provision\": label \"knowledge service\" became \"knowledge provision\"\n\
+             + Data entity \"knowledge store\"\n\
+             - Function \"knowledge management\"\n\
+             + \"knowledge provision\" provides governed interface to access \"architecture management\"\n\
+             - \"knowledge provision\" provides governed interface to access \"knowledge management\""
+        );
+        let content = d.visualization(&Options::default()).graphviz_content;
+        assert_eq!(content.matches(",color=\"#1a9850\"").count(), 1);
+        assert_eq!(content.matches("[color=\"#1a9850\"").count(), 1);
+        assert!(content.contains("style=\"rounded,filled,dashed\""));
+        assert!(d.components.iter().any(|(_, c)| *c == Change::Unchanged));
+        assert!(diff(
+            &baseline,
+            &baseline_relations,
+            &baseline,
+            &baseline_relations
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! A single HTML page to explore the architecture in a browser, without a server or network
+//! access.
+//!
+//! The page embeds the components and relations as JSON, placed as in the diagram of the
+//! built-in layout, and draws them with a script. It supports panning and zooming, searching by
+//! label, filtering by kind and domain, and shows the description and relations of the selected
+//! component in a side panel.
+
+use super::visualization::{graph, url, Options};
+use super::{ArchitectureDomain, Component, ComponentKind, Relation};
+use crate::graphviz::svg::place;
+use crate::graphviz::Value;
+use crate::xml::escape;
+use serde_json::json;
+
+const TEMPLATE: &str = include_str!("explorer.html");
+
+/// Writes the page, with the title, theme, grouping and links of the options.
+pub fn explorer(components: &[Component], relations: &[Relation], options: &Options) -> String {
+    let title = options
+        .title
+        .as_deref()
+        .unwrap_or("Architecture")
+        .replace('\n', " ");
+    let mut options = options.clone();
+    options.legend = false;
+    options.title = None;
+    let graph = graph(components, relations, &options);
+    let placement = place(&graph);
+
+    let kinds = ComponentKind::ALL
+        .iter()
+        .filter(|k| components.iter().any(|c| c.kind == **k))
+        .map(|k| {
+            json!({
+                "name": k.name(),
+                "label": k.label(),
+                "domain": k.domain().name(),
+                "fill": options.theme.node_style(*k).fill_color,
+            })
+        })
+        .collect::<Vec<_>>();
+    let domains = ArchitectureDomain::ALL
+        .iter()
+        .filter(|d| components.iter().any(|c| c.kind.domain() == **d))
+        .map(|d| json!({ "name": d.name(), "label": d.label() }))
+        .collect::<Vec<_>>();
+    let nodes = components
+        .iter()
+        .filter_map(|c| {
+            let bounds = placement.nodes.get(&c.id.value)?;
+            Some(json!({
+                "id": c.id.value,
+                "label": c.label,
+                "kind": c.kind.name(),
+                "domain": c.kind.domain().name(),
+                "description": c.description,
+                "url": url(&c.id, &options),
+                "x": bounds.x,
+                "y": bounds.y,
+                "width": bounds.width,
+                "height": bounds.height,
+            }))
+        })
+        .collect::<Vec<_>>();
+    let edges = relations
+        .iter()
+        .filter(|r| placement.nodes.contains_key(&r.from.value))
+        .filter(|r| placement.nodes.contains_key(&r.to.value))
+        .map(|r| {
+            json!({
+                "from": r.from.value,
+                "to": r.to.value,
+                "kind": r.kind.name(),
+                "label": r.kind.label(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let groups = graph
+        .subgraphs
+        .iter()
+        .filter_map(|subgraph| {
+            let bounds = placement.clusters.get(&subgraph.id)?;
+            let label = match subgraph.attributes.iter().find(|(n, _)| n == "label") {
+                Some((_, Value::Text(label))) => label.to_string(),
+                _ => String::new(),
+            };
+            Some(json!({
+                "label": label,
+                "x": bounds.x,
+                "y": bounds.y,
+                "width": bounds.width,
+                "height": bounds.height,
+            }))
+        })
+        .collect::<Vec<_>>();
+    let data = json!({
+        "width": placement.width,
+        "height": placement.height,
+        "kinds": kinds,
+        "domains": domains,
+        "groups": groups,
+        "nodes": nodes,
+        "edges": edges,
+    });
+
+    // Escaped so that no text can end the script element that holds the data.
+    let data = serde_json::to_string(&data)
+        .unwrap()
+        .replace('<', "\\u003c");
+    let (head, tail) = TEMPLATE.split_at(TEMPLATE.find("$DATA").unwrap());
+    format!(
+        "{}{}{}",
+        head.replace("$TITLE", &escape(&title)),
+        data,
+        &tail["$DATA".len()..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::explorer::explorer;
+    use crate::architecture::fixtures;
+    use crate::architecture::visualization::{Grouping, Options};
+
+    #[test]
+    fn embeds_graph_as_json() {
+        let mut components = fixtures::components();
+        components[0].label = "</script><b>".to_string();
+        let options = Options {
+            title: Some("R&D".to_string()),
+            grouping: Grouping::Domain,
+            link: Some("components/$SLUG.html".to_string()),
+            ..Options::default()
+        };
+
+        let content = explorer(&components, &fixtures::relations(), &options);
+
+        assert!(content.contains("<title>R&amp;D</title>"));
+        assert_eq!(content.matches("</script>").count(), 2);
+        let start = content.find("application/json\">").unwrap() + "application/json\">".len();
+        let end = start + content[start..].find("</script>").unwrap();
+        let data: serde_json::Value = serde_json::from_str(&content[start..end]).unwrap();
+        assert_eq!(data["nodes"][0]["label"], "</script><b>");
+        assert_eq!(
+            data["nodes"][0]["url"],
+            "components/knowledge-management.html"
+        );
+        assert_eq!(data["nodes"].as_array().unwrap().len(), components.len());
+        assert_eq!(data["edges"].as_array().unwrap().len(), 8);
+        assert_eq!(data["groups"].as_array().unwrap().len(), 2);
+        assert_eq!(data["domains"][0]["name"], "business");
+    }
+}
@@ -0,0 +1,349 @@
+//! ArchiMate 3.1 models in the Open Exchange Format, for Archi and other ArchiMate tools.
+//!
+//! Each component becomes an element and each relation a relationship, of the ArchiMate type
+//! closest to its TOGAF kind. Properties keep the IRI and the TOGAF class or property, so that
+//! importing the model again restores the components and relations. A view mirrors the
+//! diagram of the built-in layout, with a container for each cluster.
+
+use super::identifiers;
+use crate::architecture::visualization::{graph, Options};
+use crate::architecture::{Component, ComponentId, ComponentKind, Relation, RelationKind};
+use crate::graphviz::svg::{place, Bounds};
+use crate::graphviz::Value;
+use crate::xml::escape_content;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+pub const NAMESPACE: &str = "http://www.opengroup.org/xsd/archimate/3.0/";
+pub const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const SCHEMA_LOCATION: &str = "http://www.opengroup.org/xsd/archimate/3.1/archimate3_Diagram.xsd";
+
+/// The identifiers and names of the property definitions.
+pub const IRI_PROPERTY: (&str, &str) = ("propid-iri", "IRI");
+pub const CLASS_PROPERTY: (&str, &str) = ("propid-togaf-class", "TOGAF class");
+pub const RELATION_PROPERTY: (&str, &str) = ("propid-togaf-property", "TOGAF property");
+
+/// The ArchiMate element type of a kind.
+pub fn element_type(kind: ComponentKind) -> &'static str {
+    match kind {
+        ComponentKind::BusinessService => "BusinessService",
+        ComponentKind::Function => "BusinessFunction",
+        ComponentKind::InformationSystemService => "ApplicationService",
+        ComponentKind::Process => "BusinessProcess",
+        ComponentKind::LogicalApplicationComponent => "ApplicationComponent",
+        ComponentKind::PhysicalApplicationComponent => "ApplicationComponent",
+        ComponentKind::DataEntity => "DataObject",
+        ComponentKind::PlatformService => "TechnologyService",
+    }
+}
+
+/// The ArchiMate relationship type of a kind, and whether the relationship runs opposite to
+/// the relation, since ArchiMate has no inverse relationships such as "is realized through".
+pub fn relationship_type(kind: RelationKind) -> (&'static str, bool) {
+    match kind {
+        RelationKind::InformationSystemServiceRealizesBusinessService => ("Realization", false),
+        RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction => {
+            ("Serving", false)
+        }
+        RelationKind::ProcessDecomposesAndOrOrchestratesFunction => ("Aggregation", false),
+        RelationKind::FunctionIsRealizedByAndOrSupportsProcess => ("Serving", false),
+        RelationKind::LogicalApplicationComponentImplementsInformationSystemService => {
+            ("Realization", false)
+        }
+        RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent => {
+            ("Realization", true)
+        }
+        RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent => {
+            ("Realization", false)
+        }
+    }
+}
+
+/// Writes the model, named after the title of the options, with a view laid out as the diagram
+/// for these options.
+pub fn exchange(components: &[Component], relations: &[Relation], options: &Options) -> String {
+    let name = options
+        .title
+        .as_deref()
+        .unwrap_or("Architecture")
+        .to_string();
+    let names = identifiers(components);
+    let ids = names
+        .iter()
+        .map(|(id, name)| (*id, format!("id-{}", name)))
+        .collect::<HashMap<_, _>>();
+
+    let mut s = String::new();
+    writeln!(s, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(
+        s,
+        "<model xmlns=\"{}\" xmlns:xsi=\"{}\" xsi:schemaLocation=\"{} {}\" \
+         identifier=\"id-model\">",
+        NAMESPACE, XSI, NAMESPACE, SCHEMA_LOCATION
+    )
+    .unwrap();
+    writeln!(
+        s,
+        "  <name xml:lang=\"en\">{}</name>",
+        escape_content(&name)
+    )
+    .unwrap();
+
+    s.push_str("  <elements>\n");
+    for c in components.iter() {
+        writeln!(
+            s,
+            "    <element identifier=\"{}\" xsi:type=\"{}\">",
+            ids[&c.id],
+            element_type(c.kind)
+        )
+        .unwrap();
+        writeln!(
+            s,
+            "      <name xml:lang=\"en\">{}</name>",
+            escape_content(&c.label)
+        )
+        .unwrap();
+        if let Some(d) = &c.description {
+            writeln!(
+                s,
+                "      <documentation xml:lang=\"en\">{}</documentation>",
+                escape_content(d)
+            )
+            .unwrap();
+        }
+        properties(
+            &mut s,
+            &[
+                (IRI_PROPERTY.0, &c.id.value),
+                (CLASS_PROPERTY.0, &c.kind.iri()),
+            ],
+        );
+        s.push_str("    </element>\n");
+    }
+    s.push_str("  </elements>\n");
+
+    let mut relationships = vec![];
+    let mut taken = HashSet::new();
+    for r in relations.iter() {
+        let (from, to) = match (names.get(&r.from), names.get(&r.to)) {
+            (Some(from), Some(to)) => (from, to),
+            _ => continue,
+        };
+        let identifier = format!("id-{}-{}-{}", from, r.kind.name(), to);
+        if taken.insert(identifier.to_string()) {
+            relationships.push((r, identifier));
+        }
+    }
+    if !relationships.is_empty() {
+        s.push_str("  <relationships>\n");
+        for (r, identifier) in relationships.iter() {
+            let (kind, reversed) = relationship_type(r.kind);
+            let (source, target) = if reversed {
+                (&ids[&r.to], &ids[&r.from])
+            } else {
+                (&ids[&r.from], &ids[&r.to])
+            };
+            writeln!(
+                s,
+                "    <relationship identifier=\"{}\" source=\"{}\" target=\"{}\" xsi:type=\"{}\">",
+                identifier, source, target, kind
+            )
+            .unwrap();
+            if !reversed {
+                writeln!(
+                    s,
+                    "      <name xml:lang=\"en\">{}</name>",
+                    escape_content(r.kind.label())
+                )
+                .unwrap();
+            }
+            properties(&mut s, &[(RELATION_PROPERTY.0, &r.kind.iri())]);
+            s.push_str("    </relationship>\n");
+        }
+        s.push_str("  </relationships>\n");
+    }
+
+    s.push_str("  <propertyDefinitions>\n");
+    for (identifier, name) in [IRI_PROPERTY, CLASS_PROPERTY, RELATION_PROPERTY].iter() {
+        writeln!(
+            s,
+            "    <propertyDefinition identifier=\"{}\" type=\"string\">\n\
+             \x20     <name xml:lang=\"en\">{}</name>\n\
+             \x20   </propertyDefinition>",
+            identifier, name
+        )
+        .unwrap();
+    }
+    s.push_str("  </propertyDefinitions>\n");
+
+    view(
+        &mut s,
+        components,
+        relations,
+        &relationships,
+        &ids,
+        &name,
+        options,
+    );
+    s.push_str("</model>\n");
+    s
+}
+
+fn properties(s: &mut String, properties: &[(&str, &str)]) {
+    s.push_str("      <properties>\n");
+    for (definition, value) in properties.iter() {
+        writeln!(
+            s,
+            "        <property propertyDefinitionRef=\"{}\">\
+             <value xml:lang=\"en\">{}</value></property>",
+            definition,
+            escape_content(value)
+        )
+        .unwrap();
+    }
+    s.push_str("      </properties>\n");
+}
+
+/// Writes a view with the nodes and clusters where the built-in layout places them, and
+/// without the legend.
+fn view(
+    s: &mut String,
+    components: &[Component],
+    relations: &[Relation],
+    relationships: &[(&Relation, String)],
+    ids: &HashMap<&ComponentId, String>,
+    name: &str,
+    options: &Options,
+) {
+    let mut options = options.clone();
+    options.legend = false;
+    let graph = graph(components, relations, &options);
+    let placement = place(&graph);
+    let bounds = |b: &Bounds| {
+        format!(
+            "x=\"{:.0}\" y=\"{:.0}\" w=\"{:.0}\" h=\"{:.0}\"",
+            b.x,
+            b.y,
+            b.width.max(1.0),
+            b.height.max(1.0)
+        )
+    };
+    let by_iri = components
+        .iter()
+        .map(|c| (c.id.value.as_str(), c))
+        .collect::<HashMap<_, _>>();
+    let node = |s: &mut String, iri: &str, indent: &str| {
+        if let (Some(c), Some(b)) = (by_iri.get(iri), placement.nodes.get(iri)) {
+            let element = &ids[&c.id];
+            writeln!(
+                s,
+                "{}<node identifier=\"view-{}\" elementRef=\"{}\" xsi:type=\"Element\" {}/>",
+                indent,
+                element,
+                element,
+                bounds(b)
+            )
+            .unwrap();
+        }
+    };
+
+    s.push_str("  <views>\n    <diagrams>\n");
+    writeln!(
+        s,
+        "      <view identifier=\"id-view\" xsi:type=\"Diagram\">\n\
+         \x20       <name xml:lang=\"en\">{}</name>",
+        escape_content(name)
+    )
+    .unwrap();
+    for n in graph.nodes.iter() {
+        node(s, &n.id, "        ");
+    }
+    for subgraph in graph.subgraphs.iter() {
+        let b = match placement.clusters.get(&subgraph.id) {
+            Some(b) => b,
+            None => continue,
+        };
+        writeln!(
+            s,
+            "        <node identifier=\"view-{}\" xsi:type=\"Container\" {}>",
+            subgraph.id,
+            bounds(b)
+        )
+        .unwrap();
+        let label = subgraph.attributes.iter().find(|(n, _)| n == "label");
+        if let Some((_, Value::Text(label))) = label {
+            writeln!(
+                s,
+                "          <label xml:lang=\"en\">{}</label>",
+                escape_content(label)
+            )
+            .unwrap();
+        }
+        for n in subgraph.nodes.iter() {
+            node(s, &n.id, "          ");
+        }
+        s.push_str("        </node>\n");
+    }
+    for (r, identifier) in relationships.iter() {
+        let (_, reversed) = relationship_type(r.kind);
+        let (source, target) = if reversed {
+            (&ids[&r.to], &ids[&r.from])
+        } else {
+            (&ids[&r.from], &ids[&r.to])
+        };
+        writeln!(
+            s,
+            "        <connection identifier=\"view-{}\" relationshipRef=\"{}\" \
+             xsi:type=\"Relationship\" source=\"view-{}\" target=\"view-{}\"/>",
+            identifier, identifier, source, target
+        )
+        .unwrap();
+    }
+    s.push_str("      </view>\n    </diagrams>\n  </views>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::export::archimate::{exchange, NAMESPACE, XSI};
+    use crate::architecture::fixtures;
+    use crate::architecture::visualization::{Grouping, Options};
+
+    #[test]
+    fn writes_elements_relationships_and_view() {
+        let mut components = fixtures::components();
+        components[0].label = "R&D <management>".to_string();
+        components[0].description = Some("Plans.\n\nDelivers.".to_string());
+        let options = Options {
+            grouping: Grouping::Domain,
+            ..Options::default()
+        };
+
+        let content = exchange(&components, &fixtures::relations(), &options);
+
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let count = |name: &str, kind: &str| {
+            document
+                .descendants()
+                .filter(|n| n.has_tag_name((NAMESPACE, name)))
+                .filter(|n| n.attribute((XSI, "type")) == Some(kind))
+                .count()
+        };
+        assert_eq!(count("element", "BusinessFunction"), 2);
+        assert_eq!(count("element", "ApplicationComponent"), 1);
+        assert_eq!(count("relationship", "Realization"), 4);
+        assert_eq!(count("node", "Element"), components.len());
+        assert_eq!(count("node", "Container"), 2);
+        assert_eq!(count("connection", "Relationship"), 8);
+        assert!(content.contains(">R&amp;D &lt;management&gt;</name>"));
+        let documentation = document
+            .descendants()
+            .find(|n| n.has_tag_name((NAMESPACE, "documentation")))
+            .unwrap();
+        assert_eq!(documentation.text(), Some("Plans.\n\nDelivers."));
+        assert!(content.contains(
+            "source=\"id-knowledge_application_service_component\" \
+             target=\"id-architecture_application_service\" xsi:type=\"Realization\""
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+//! Mermaid flowcharts, as rendered by GitLab, GitHub and many wikis.
+//!
+//! Mermaid has no stereotypes, so each kind is a class styled after the theme, and the kind
+//! label shows in guillemets below the component label. Node identifiers are prefixed with `c_`,
+//! since Mermaid reads some words, such as `end`, as keywords.
+
+use super::identifiers;
+use crate::architecture::theme::{NodeStyle, Theme};
+use crate::architecture::{Component, ComponentKind, Relation};
+use std::collections::HashMap;
+
+pub fn flowchart(components: &[Component], relations: &[Relation], theme: &Theme) -> String {
+    let ids = identifiers(components)
+        .into_iter()
+        .map(|(id, identifier)| (id, format!("c_{}", identifier)))
+        .collect::<HashMap<_, _>>();
+    let mut lines = vec!["flowchart TB".to_string()];
+    for kind in ComponentKind::ALL.iter() {
+        if components.iter().any(|c| c.kind == *kind) {
+            let class = class(&theme.node_style(*kind));
+            if !class.is_empty() {
+                lines.push(format!("    classDef {} {}", kind.name(), class));
+            }
+        }
+    }
+    for c in components.iter() {
+        let (open, close) = match c.kind {
+            ComponentKind::BusinessService
+            | ComponentKind::InformationSystemService
+            | ComponentKind::PlatformService => ("([", "])"),
+            ComponentKind::Process => (">", "]"),
+            ComponentKind::DataEntity => ("[(", ")]"),
+            _ => ("[", "]"),
+        };
+        lines.push(format!(
+            "    {}{}\"{}<br/>«{}»\"{}:::{}",
+            ids[&c.id],
+            open,
+            escape(&c.label),
+            escape(c.kind.label()),
+            close,
+            c.kind.name()
+        ));
+    }
+    for r in relations.iter() {
+        if let (Some(from), Some(to)) = (ids.get(&r.from), ids.get(&r.to)) {
+            let dashed = matches!(theme.edge_style(r.kind).style, Some(s) if s.contains("dashed"));
+            let arrow = if dashed { "-.->" } else { "-->" };
+            lines.push(format!(
+                "    {} {}|\"{}\"| {}",
+                from,
+                arrow,
+                escape(r.kind.label()),
+                to
+            ));
+        }
+    }
+    let mut s = lines.join("\n");
+    s.push('\n');
+    s
+}
+
+/// The style properties of a class, as in `fill:#fff2cc,stroke:#bdbdbd`.
+fn class(style: &NodeStyle) -> String {
+    let mut properties = vec![];
+    if let Some(fill) = &style.fill_color {
+        properties.push(format!("fill:{}", fill));
+    }
+    if let Some(color) = &style.color {
+        properties.push(format!("stroke:{}", color));
+    }
+    if let Some(color) = &style.font_color {
+        properties.push(format!("color:{}", color));
+    }
+    if matches!(&style.style, Some(s) if s.contains("dashed")) {
+        properties.push("stroke-dasharray:5 2".to_string());
+    }
+    properties.join(",")
+}
+
+/// Escapes text for a quoted label, with entity codes for the characters that Mermaid would
+/// otherwise read as syntax or markup, and line breaks as `<br/>`.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("#quot;"),
+            '#' => escaped.push_str("#35;"),
+            '&' => escaped.push_str("#amp;"),
+            '<' => escaped.push_str("#lt;"),
+            '>' => escaped.push_str("#gt;"),
+            '\n' => escaped.push_str("<br/>"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::export::mermaid::{escape, flowchart};
+    use crate::architecture::fixtures;
+    use crate::architecture::theme::Theme;
+    use crate::architecture::{ComponentKind, RelationKind};
+
+    #[test]
+    fn matches_golden_file() {
+        let mut components = fixtures::components();
+        components.push(fixtures::component("end", "end", ComponentKind::Process));
+        let mut relations = fixtures::relations();
+        relations.push(fixtures::relation(
+            "end",
+            RelationKind::ProcessDecomposesAndOrOrchestratesFunction,
+            "knowledge-management",
+        ));
+
+        let content = flowchart(&components, &relations, &Theme::togaf());
+
+        assert_eq!(content, include_str!("golden/fixtures.mmd"));
+    }
+
+    #[test]
+    fn escapes_syntax_in_labels() {
+        assert_eq!(
+            escape("\"R&D\" <#1>\nnext"),
+            "#quot;R#amp;D#quot; #lt;#35;1#gt;<br/>next"
+        );
+    }
+}
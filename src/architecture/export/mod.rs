@@ -0,0 +1,36 @@
+//! Exports of components and relations to the formats of other diagramming and modelling
+//! tools.
+
+use super::{Component, ComponentId};
+use std::collections::{HashMap, HashSet};
+
+pub mod archimate;
+pub mod mermaid;
+pub mod network;
+pub mod plantuml;
+pub mod structurizr;
+
+/// An identifier for each component that is valid in most formats, based on its slug with
+/// underscores, and numbered where slugs collide.
+fn identifiers(components: &[Component]) -> HashMap<&ComponentId, String> {
+    let mut taken = HashSet::new();
+    let mut identifiers = HashMap::new();
+    for c in components.iter() {
+        let base = match c.id.slug().replace('-', "_") {
+            s if s.starts_with(|c: char| c.is_ascii_alphabetic()) => s,
+            s => format!("c_{}", s),
+        };
+        let base = base
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let mut identifier = base.to_string();
+        let mut n = 1;
+        while !taken.insert(identifier.to_string()) {
+            n += 1;
+            identifier = format!("{}_{}", base, n);
+        }
+        identifiers.insert(&c.id, identifier);
+    }
+    identifiers
+}
@@ -0,0 +1,456 @@
+//! Graph files for network analysis tools, such as Gephi, yEd and Cytoscape: GraphML, GEXF
+//! and the JSON Graph Format.
+//!
+//! Each component is a node, identified by its IRI, with its label, kind, description and
+//! graph as attributes. Each relation is an edge with its kind and label. Extra attributes
+//! follow these. Each is declared with one type, so all its values must have that type.
+
+use crate::architecture::{Component, ComponentId, Relation};
+use crate::xml::escape;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+/// The value of an attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Integer(i64),
+    Number(f64),
+    Boolean(bool),
+}
+
+impl Value {
+    /// The name of the type, which GraphML and GEXF share.
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Text(_) => "string",
+            Value::Integer(_) => "long",
+            Value::Number(_) => "double",
+            Value::Boolean(_) => "boolean",
+        }
+    }
+
+    fn json(&self) -> serde_json::Value {
+        match self {
+            Value::Text(s) => json!(s),
+            Value::Integer(i) => json!(i),
+            Value::Number(n) => json!(n),
+            Value::Boolean(b) => json!(b),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Text(s) => write!(f, "{}", s),
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Attributes beyond the standard ones, such as the results of other analyses. Their names
+/// must differ from those of the standard attributes.
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    pub components: HashMap<ComponentId, Vec<(String, Value)>>,
+    pub relations: HashMap<Relation, Vec<(String, Value)>>,
+}
+
+/// The names of the standard attributes, which extra attributes cannot have.
+const STANDARD: [&str; 4] = ["label", "kind", "description", "graph"];
+
+/// The attributes of the nodes and edges, in order, with the relations between components,
+/// and the declarations of their names and types.
+struct Network<'a> {
+    nodes: Vec<(&'a Component, Vec<(String, Value)>)>,
+    edges: Vec<(&'a Relation, Vec<(String, Value)>)>,
+    node_declarations: Vec<(String, &'static str)>,
+    edge_declarations: Vec<(String, &'static str)>,
+}
+
+impl<'a> Network<'a> {
+    /// Collects the attributes, or gives an error if an extra attribute has the name of a
+    /// standard one or values of different types.
+    fn new(
+        components: &'a [Component],
+        relations: &'a [Relation],
+        attributes: &Attributes,
+    ) -> Result<Network<'a>, String> {
+        let extra = attributes
+            .components
+            .values()
+            .chain(attributes.relations.values())
+            .flatten();
+        if let Some((name, _)) = extra
+            .into_iter()
+            .find(|(n, _)| STANDARD.contains(&n.as_str()))
+        {
+            return Err(format!("attribute {} is a standard attribute", name));
+        }
+        let known = |id: &ComponentId| components.iter().any(|c| c.id == *id);
+        let nodes = components
+            .iter()
+            .map(|c| {
+                let mut values = vec![("kind".to_string(), Value::Text(c.kind.name().to_string()))];
+                if let Some(d) = &c.description {
+                    values.push(("description".to_string(), Value::Text(d.to_string())));
+                }
+                values.push(("graph".to_string(), Value::Text(c.graph.to_string())));
+                values.extend(
+                    attributes
+                        .components
+                        .get(&c.id)
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+                (c, values)
+            })
+            .collect::<Vec<_>>();
+        let edges = relations
+            .iter()
+            .filter(|r| known(&r.from) && known(&r.to))
+            .map(|r| {
+                let mut values = vec![("kind".to_string(), Value::Text(r.kind.name().to_string()))];
+                values.extend(attributes.relations.get(r).cloned().unwrap_or_default());
+                (r, values)
+            })
+            .collect::<Vec<_>>();
+        Ok(Network {
+            node_declarations: declarations(&nodes)?,
+            edge_declarations: declarations(&edges)?,
+            nodes,
+            edges,
+        })
+    }
+}
+
+/// The names of the attributes with their types, in order of appearance, or an error if an
+/// attribute has values of different types.
+fn declarations<T>(
+    items: &[(T, Vec<(String, Value)>)],
+) -> Result<Vec<(String, &'static str)>, String> {
+    let mut declarations: Vec<(String, &'static str)> = vec![];
+    for (_, values) in items.iter() {
+        for (name, value) in values.iter() {
+            match declarations.iter().find(|(n, _)| n == name) {
+                None => declarations.push((name.to_string(), value.type_name())),
+                Some((_, t)) if *t != value.type_name() => {
+                    return Err(format!(
+                        "attribute {} has values of types {} and {}",
+                        name,
+                        t,
+                        value.type_name()
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    Ok(declarations)
+}
+
+pub fn graphml(
+    components: &[Component],
+    relations: &[Relation],
+    attributes: &Attributes,
+) -> Result<String, String> {
+    let network = Network::new(components, relations, attributes)?;
+    let mut s = String::new();
+    s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    let mut keys = HashMap::new();
+    let mut declare = |s: &mut String, domain: &'static str, name: &str, kind: &str| {
+        let id = format!("d{}", keys.len());
+        writeln!(
+            s,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+            id,
+            domain,
+            escape(name),
+            kind
+        )
+        .unwrap();
+        keys.insert((domain, name.to_string()), id);
+    };
+    declare(&mut s, "node", "label", "string");
+    for (name, kind) in network.node_declarations.iter() {
+        declare(&mut s, "node", name, kind);
+    }
+    declare(&mut s, "edge", "label", "string");
+    for (name, kind) in network.edge_declarations.iter() {
+        declare(&mut s, "edge", name, kind);
+    }
+    let data = |s: &mut String, domain: &'static str, name: &str, value: &str| {
+        writeln!(
+            s,
+            "      <data key=\"{}\">{}</data>",
+            keys[&(domain, name.to_string())],
+            escape(value)
+        )
+        .unwrap();
+    };
+
+    s.push_str("  <graph id=\"architecture\" edgedefault=\"directed\">\n");
+    for (c, values) in network.nodes.iter() {
+        writeln!(s, "    <node id=\"{}\">", escape(&c.id.value)).unwrap();
+        data(&mut s, "node", "label", &c.label);
+        for (name, value) in values.iter() {
+            data(&mut s, "node", name, &value.to_string());
+        }
+        s.push_str("    </node>\n");
+    }
+    for (i, (r, values)) in network.edges.iter().enumerate() {
+        writeln!(
+            s,
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">",
+            i,
+            escape(&r.from.value),
+            escape(&r.to.value)
+        )
+        .unwrap();
+        data(&mut s, "edge", "label", r.kind.label());
+        for (name, value) in values.iter() {
+            data(&mut s, "edge", name, &value.to_string());
+        }
+        s.push_str("    </edge>\n");
+    }
+    s.push_str("  </graph>\n</graphml>\n");
+    Ok(s)
+}
+
+pub fn gexf(
+    components: &[Component],
+    relations: &[Relation],
+    attributes: &Attributes,
+) -> Result<String, String> {
+    let network = Network::new(components, relations, attributes)?;
+    let mut s = String::new();
+    s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s.push_str("<gexf xmlns=\"http://www.gexf.net/1.2draft\" version=\"1.2\">\n");
+    s.push_str("  <graph defaultedgetype=\"directed\" mode=\"static\">\n");
+    let classes = [
+        ("node", &network.node_declarations),
+        ("edge", &network.edge_declarations),
+    ];
+    for (class, declarations) in classes.iter() {
+        writeln!(s, "    <attributes class=\"{}\">", class).unwrap();
+        for (i, (name, kind)) in declarations.iter().enumerate() {
+            writeln!(
+                s,
+                "      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>",
+                i,
+                escape(name),
+                kind
+            )
+            .unwrap();
+        }
+        s.push_str("    </attributes>\n");
+    }
+    let values =
+        |s: &mut String, declarations: &[(String, &'static str)], values: &[(String, Value)]| {
+            s.push_str("        <attvalues>\n");
+            for (name, value) in values.iter() {
+                let i = declarations.iter().position(|(n, _)| n == name).unwrap();
+                writeln!(
+                    s,
+                    "          <attvalue for=\"{}\" value=\"{}\"/>",
+                    i,
+                    escape(&value.to_string())
+                )
+                .unwrap();
+            }
+            s.push_str("        </attvalues>\n");
+        };
+
+    s.push_str("    <nodes>\n");
+    for (c, attributes) in network.nodes.iter() {
+        writeln!(
+            s,
+            "      <node id=\"{}\" label=\"{}\">",
+            escape(&c.id.value),
+            escape(&c.label)
+        )
+        .unwrap();
+        values(&mut s, &network.node_declarations, attributes);
+        s.push_str("      </node>\n");
+    }
+    s.push_str("    </nodes>\n    <edges>\n");
+    for (i, (r, attributes)) in network.edges.iter().enumerate() {
+        writeln!(
+            s,
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\" label=\"{}\">",
+            i,
+            escape(&r.from.value),
+            escape(&r.to.value),
+            escape(r.kind.label())
+        )
+        .unwrap();
+        values(&mut s, &network.edge_declarations, attributes);
+        s.push_str("      </edge>\n");
+    }
+    s.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    Ok(s)
+}
+
+/// Writes the graph in version 1 of the JSON Graph Format, with the attributes as metadata.
+pub fn json_graph(
+    components: &[Component],
+    relations: &[Relation],
+    attributes: &Attributes,
+) -> Result<String, String> {
+    let network = Network::new(components, relations, attributes)?;
+    let metadata = |values: &[(String, Value)]| {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.json()))
+            .collect::<serde_json::Map<_, _>>()
+    };
+    let nodes = network
+        .nodes
+        .iter()
+        .map(|(c, values)| {
+            json!({
+                "id": c.id.value,
+                "label": c.label,
+                "metadata": metadata(values),
+            })
+        })
+        .collect::<Vec<_>>();
+    let edges = network
+        .edges
+        .iter()
+        .map(|(r, values)| {
+            json!({
+                "source": r.from.value,
+                "target": r.to.value,
+                "relation": r.kind.name(),
+                "label": r.kind.label(),
+                "metadata": metadata(values),
+            })
+        })
+        .collect::<Vec<_>>();
+    let graph = json!({
+        "graph": {
+            "directed": true,
+            "nodes": nodes,
+            "edges": edges,
+        }
+    });
+    let mut s = serde_json::to_string_pretty(&graph).unwrap();
+    s.push('\n');
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::export::network::{gexf, graphml, json_graph, Attributes, Value};
+    use crate::architecture::fixtures;
+
+    fn attributes() -> Attributes {
+        let mut attributes = Attributes::default();
+        attributes.components.insert(
+            fixtures::id("knowledge-service"),
+            vec![("cycles".to_string(), Value::Integer(2))],
+        );
+        attributes.relations.insert(
+            fixtures::relations()[0].clone(),
+            vec![("weight".to_string(), Value::Number(0.5))],
+        );
+        attributes
+    }
+
+    #[test]
+    fn writes_graphml_with_attributes() {
+        let components = fixtures::components();
+        let content = graphml(&components, &fixtures::relations(), &attributes()).unwrap();
+
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let elements = |name: &str| {
+            document
+                .descendants()
+                .filter(|n| n.tag_name().name() == name)
+                .collect::<Vec<_>>()
+        };
+        let key = |name: &str| {
+            elements("key")
+                .into_iter()
+                .find(|k| k.attribute("attr.name") == Some(name))
+                .map(|k| {
+                    (
+                        k.attribute("id").unwrap(),
+                        k.attribute("attr.type").unwrap(),
+                    )
+                })
+                .unwrap()
+        };
+        assert_eq!(elements("node").len(), components.len());
+        assert_eq!(elements("edge").len(), fixtures::relations().len());
+        assert_eq!(key("cycles").1, "long");
+        assert_eq!(key("weight").1, "double");
+        let cycles = elements("data")
+            .into_iter()
+            .find(|d| d.attribute("key") == Some(key("cycles").0))
+            .unwrap();
+        assert_eq!(cycles.text(), Some("2"));
+        assert_eq!(
+            cycles.parent().unwrap().attribute("id"),
+            Some(fixtures::id("knowledge-service").value.as_str())
+        );
+    }
+
+    #[test]
+    fn writes_gexf_and_json_graph() {
+        let mut components = fixtures::components();
+        components[0].description = Some("R&D \"models\"".to_string());
+        let relations = fixtures::relations();
+
+        let content = gexf(&components, &relations, &attributes()).unwrap();
+
+        assert!(roxmltree::Document::parse(&content).is_ok());
+        assert!(content.contains("<attribute id=\"3\" title=\"cycles\" type=\"long\"/>"));
+        assert!(content.contains("<attvalue for=\"1\" value=\"R&amp;D &quot;models&quot;\"/>"));
+        assert!(content.contains("<attvalue for=\"1\" value=\"0.5\"/>"));
+
+        let content = json_graph(&components, &relations, &attributes()).unwrap();
+
+        let graph: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(graph["graph"]["nodes"][1]["metadata"]["cycles"], 2);
+        assert_eq!(graph["graph"]["nodes"][0]["metadata"]["kind"], "Function");
+        assert_eq!(graph["graph"]["edges"][0]["metadata"]["weight"], 0.5);
+        assert_eq!(
+            graph["graph"]["edges"][0]["label"],
+            relations[0].kind.label()
+        );
+    }
+
+    #[test]
+    fn rejects_ambiguous_attributes() {
+        let components = fixtures::components();
+        let relations = fixtures::relations();
+        let mut attributes = attributes();
+        attributes.components.insert(
+            fixtures::id("knowledge-management"),
+            vec![("cycles".to_string(), Value::Text("two".to_string()))],
+        );
+
+        assert_eq!(
+            graphml(&components, &relations, &attributes),
+            Err("attribute cycles has values of types string and long".to_string())
+        );
+
+        let mut attributes = Attributes::default();
+        attributes.relations.insert(
+            relations[0].clone(),
+            vec![("label".to_string(), Value::Boolean(true))],
+        );
+
+        assert_eq!(
+            json_graph(&components, &relations, &attributes),
+            Err("attribute label is a standard attribute".to_string())
+        );
+    }
+}
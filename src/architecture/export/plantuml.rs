@@ -0,0 +1,121 @@
+//! PlantUML component diagrams, with each kind as a stereotype styled after the theme.
+
+use super::identifiers;
+use crate::architecture::theme::Theme;
+use crate::architecture::{Component, ComponentKind, Relation};
+
+pub fn component_diagram(
+    components: &[Component],
+    relations: &[Relation],
+    theme: &Theme,
+) -> String {
+    let ids = identifiers(components);
+    let mut lines = vec!["@startuml".to_string()];
+    let kinds = ComponentKind::ALL
+        .iter()
+        .filter(|k| components.iter().any(|c| c.kind == **k))
+        .collect::<Vec<_>>();
+    if !kinds.is_empty() {
+        lines.push("skinparam component {".to_string());
+        for kind in kinds.iter() {
+            let style = theme.node_style(**kind);
+            let parameters = [
+                ("BackgroundColor", &style.fill_color),
+                ("BorderColor", &style.color),
+                ("FontColor", &style.font_color),
+            ];
+            for (name, value) in parameters.iter() {
+                if let Some(value) = value {
+                    lines.push(format!("  {}<<{}>> {}", name, kind.name(), color(value)));
+                }
+            }
+        }
+        lines.push("}".to_string());
+    }
+    for c in components.iter() {
+        lines.push(format!(
+            "component \"{}\" as {} <<{}>>",
+            escape(&c.label),
+            ids[&c.id],
+            c.kind.name()
+        ));
+    }
+    for r in relations.iter() {
+        if let (Some(from), Some(to)) = (ids.get(&r.from), ids.get(&r.to)) {
+            let dashed = matches!(theme.edge_style(r.kind).style, Some(s) if s.contains("dashed"));
+            let arrow = if dashed { "..>" } else { "-->" };
+            lines.push(format!(
+                "{} {} {} : {}",
+                from,
+                arrow,
+                to,
+                escape(r.kind.label())
+            ));
+        }
+    }
+    lines.push("@enduml".to_string());
+    let mut s = lines.join("\n");
+    s.push('\n');
+    s
+}
+
+/// Writes a Graphviz color as a PlantUML color, which names without `#`.
+fn color(value: &str) -> String {
+    if value.starts_with('#') {
+        value.to_string()
+    } else {
+        format!("#{}", value)
+    }
+}
+
+/// Escapes text for a quoted name or a relation label. Characters that could end the text or
+/// start markup become numeric character references, and doubled creole markers, such as `**`
+/// for bold, are escaped with `~`.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' => escaped.push_str("&#34;"),
+            '&' => escaped.push_str("&#38;"),
+            '<' => escaped.push_str("&#60;"),
+            '>' => escaped.push_str("&#62;"),
+            '~' => escaped.push_str("~~"),
+            c if "*_/-^=".contains(c) && chars.peek() == Some(&c) => {
+                escaped.push('~');
+                escaped.push(c);
+            }
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::export::plantuml::{component_diagram, escape};
+    use crate::architecture::fixtures;
+    use crate::architecture::theme::Theme;
+
+    #[test]
+    fn matches_golden_file() {
+        let content = component_diagram(
+            &fixtures::components(),
+            &fixtures::relations(),
+            &Theme::togaf(),
+        );
+
+        assert_eq!(content, include_str!("golden/fixtures.puml"));
+    }
+
+    #[test]
+    fn escapes_syntax_in_labels() {
+        assert_eq!(
+            escape("\"R&D\" <b>**x** C:\\\n~"),
+            "&#34;R&#38;D&#34; &#60;b&#62;~**x~** C:\\\\\\n~~"
+        );
+    }
+}
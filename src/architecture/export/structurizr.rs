@@ -0,0 +1,424 @@
+//! Structurizr DSL workspaces, for C4 diagrams.
+//!
+//! Logical application components become software systems, or the containers of one named
+//! software system, and the physical application components that extend them become their
+//! containers or components. The information system services that a component implements
+//! are its responsibilities, listed in its description. Components of the other kinds become
+//! custom elements. Relations become relationships between these elements, with the ends that
+//! are services moved to the components responsible for them.
+
+use super::identifiers;
+use crate::architecture::theme::Theme;
+use crate::architecture::{Component, ComponentId, ComponentKind, Relation, RelationKind};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Writes a workspace with a system context view and, where there are containers, a container
+/// view for each software system. With a system name, the logical application components are
+/// the containers of a single software system with that name.
+pub fn workspace(
+    components: &[Component],
+    relations: &[Relation],
+    system: Option<&str>,
+    theme: &Theme,
+) -> String {
+    let ids = identifiers(components);
+    let by_id = components
+        .iter()
+        .map(|c| (&c.id, c))
+        .collect::<HashMap<_, _>>();
+    let is =
+        |id: &ComponentId, kind: ComponentKind| matches!(by_id.get(id), Some(c) if c.kind == kind);
+
+    let mut responsible: HashMap<&ComponentId, Vec<&ComponentId>> = HashMap::new();
+    let mut parents = HashMap::new();
+    for r in relations.iter() {
+        let (service, component) = match r.kind {
+            RelationKind::LogicalApplicationComponentImplementsInformationSystemService => {
+                (&r.to, &r.from)
+            }
+            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent => {
+                (&r.from, &r.to)
+            }
+            RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent => {
+                if is(&r.from, ComponentKind::PhysicalApplicationComponent)
+                    && is(&r.to, ComponentKind::LogicalApplicationComponent)
+                {
+                    parents.entry(&r.from).or_insert(&r.to);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if is(service, ComponentKind::InformationSystemService)
+            && is(component, ComponentKind::LogicalApplicationComponent)
+        {
+            let components = responsible.entry(service).or_default();
+            if !components.contains(&component) {
+                components.push(component);
+            }
+        }
+    }
+    let responsibilities = |id: &ComponentId| {
+        components
+            .iter()
+            .filter(|s| matches!(responsible.get(&s.id), Some(r) if r.contains(&id)))
+            .collect::<Vec<_>>()
+    };
+    let children = |id: &ComponentId| {
+        components
+            .iter()
+            .filter(|c| parents.get(&c.id) == Some(&id))
+            .collect::<Vec<_>>()
+    };
+    let elements = components
+        .iter()
+        .filter(|c| !responsible.contains_key(&c.id))
+        .collect::<Vec<_>>();
+
+    // The elements that each end of a relation stands for.
+    let ends = |id: &ComponentId| match responsible.get(id) {
+        Some(components) => components.clone(),
+        None => by_id
+            .get_key_value(id)
+            .map(|(id, _)| *id)
+            .into_iter()
+            .collect(),
+    };
+    let mut relationships = vec![];
+    let mut taken = HashSet::new();
+    for r in relations.iter() {
+        for from in ends(&r.from).iter() {
+            for to in ends(&r.to).iter() {
+                let nested =
+                    from == to || parents.get(from) == Some(to) || parents.get(to) == Some(from);
+                if !nested && taken.insert((*from, *to, r.kind.label())) {
+                    relationships.push((*from, *to, r.kind.label()));
+                }
+            }
+        }
+    }
+    // The custom elements that are related to any of the given elements.
+    let related = |scope: &[&ComponentId]| {
+        let mut related: Vec<&String> = vec![];
+        for (from, to, _) in relationships.iter() {
+            for (inside, other) in [(from, to), (to, from)].iter() {
+                let custom = !matches!(
+                    by_id[**other].kind,
+                    ComponentKind::LogicalApplicationComponent
+                        | ComponentKind::PhysicalApplicationComponent
+                );
+                if scope.contains(inside) && custom && !related.contains(&&ids[**other]) {
+                    related.push(&ids[**other]);
+                }
+            }
+        }
+        related
+    };
+
+    let mut s = String::new();
+    writeln!(
+        s,
+        "workspace \"{}\" {{\n\n    model {{",
+        escape(system.unwrap_or("Architecture"))
+    )
+    .unwrap();
+    let system_id = system.map(|_| {
+        let mut id = "system".to_string();
+        while ids.values().any(|i| *i == id) {
+            id.push('_');
+        }
+        id
+    });
+    let (levels, indent) = match (system, &system_id) {
+        (Some(name), Some(id)) => {
+            writeln!(s, "        {} = softwareSystem \"{}\" {{", id, escape(name)).unwrap();
+            (["container", "component"], "    ")
+        }
+        _ => (["softwareSystem", "container"], ""),
+    };
+    let mut views = vec![];
+    for c in elements.iter() {
+        match c.kind {
+            ComponentKind::LogicalApplicationComponent => {
+                let nested = children(&c.id);
+                element(
+                    &mut s,
+                    indent,
+                    &ids[&c.id],
+                    levels[0],
+                    c,
+                    &responsibilities(&c.id),
+                );
+                if nested.is_empty() {
+                    s.push('\n');
+                } else {
+                    s.push_str(" {\n");
+                    for n in nested.iter() {
+                        element(
+                            &mut s,
+                            &format!("{}    ", indent),
+                            &ids[&n.id],
+                            levels[1],
+                            n,
+                            &[],
+                        );
+                        s.push('\n');
+                    }
+                    writeln!(s, "        {}}}", indent).unwrap();
+                }
+                if !nested.is_empty() {
+                    let mut scope = vec![&c.id];
+                    scope.extend(nested.iter().map(|n| &n.id));
+                    views.push((&ids[&c.id], related(&scope)));
+                }
+            }
+            ComponentKind::PhysicalApplicationComponent if parents.contains_key(&c.id) => {}
+            ComponentKind::PhysicalApplicationComponent => {
+                element(&mut s, indent, &ids[&c.id], levels[0], c, &[]);
+                s.push('\n');
+            }
+            _ if system.is_some() => {}
+            _ => custom(&mut s, &ids[&c.id], c),
+        }
+    }
+    if system.is_some() {
+        s.push_str("        }\n");
+        for c in elements.iter() {
+            if !matches!(
+                c.kind,
+                ComponentKind::LogicalApplicationComponent
+                    | ComponentKind::PhysicalApplicationComponent
+            ) {
+                custom(&mut s, &ids[&c.id], c);
+            }
+        }
+    }
+    if !relationships.is_empty() {
+        s.push('\n');
+    }
+    for (from, to, label) in relationships.iter() {
+        writeln!(
+            s,
+            "        {} -> {} \"{}\"",
+            ids[from],
+            ids[to],
+            escape(label)
+        )
+        .unwrap();
+    }
+    s.push_str("    }\n\n    views {\n");
+
+    let view = |s: &mut String, kind: &str, scope: &str, suffix: &str, related: &[&String]| {
+        let mut include = vec!["*"];
+        include.extend(related.iter().map(|r| r.as_str()));
+        writeln!(
+            s,
+            "        {} {} \"{}_{}\" {{\n            include {}\n            autoLayout\n        }}",
+            kind,
+            scope,
+            scope,
+            suffix,
+            include.join(" ")
+        )
+        .unwrap();
+    };
+    match &system_id {
+        Some(id) => {
+            let scope = elements.iter().map(|c| &c.id).collect::<Vec<_>>();
+            let related = related(&scope);
+            view(&mut s, "systemContext", id, "context", &related);
+            view(&mut s, "container", id, "containers", &related);
+            for (container, related) in views.iter() {
+                view(&mut s, "component", container, "components", related);
+            }
+        }
+        None => {
+            for c in elements.iter() {
+                if matches!(
+                    c.kind,
+                    ComponentKind::LogicalApplicationComponent
+                        | ComponentKind::PhysicalApplicationComponent
+                ) {
+                    let mut scope = vec![&c.id];
+                    scope.extend(children(&c.id).iter().map(|n| &n.id));
+                    view(
+                        &mut s,
+                        "systemContext",
+                        &ids[&c.id],
+                        "context",
+                        &related(&scope),
+                    );
+                }
+            }
+            for (system, related) in views.iter() {
+                view(&mut s, "container", system, "containers", related);
+            }
+        }
+    }
+
+    s.push_str("\n        styles {\n");
+    for kind in ComponentKind::ALL.iter() {
+        if !elements.iter().any(|c| c.kind == *kind) {
+            continue;
+        }
+        let style = theme.node_style(*kind);
+        let shape = match kind {
+            ComponentKind::BusinessService
+            | ComponentKind::InformationSystemService
+            | ComponentKind::PlatformService => Some("RoundedBox"),
+            ComponentKind::DataEntity => Some("Cylinder"),
+            ComponentKind::LogicalApplicationComponent
+            | ComponentKind::PhysicalApplicationComponent => Some("Component"),
+            _ => None,
+        };
+        let properties = [
+            ("background", style.fill_color.as_deref().map(color)),
+            ("color", style.font_color.as_deref().map(color)),
+            ("stroke", style.color.as_deref().map(color)),
+            ("shape", shape.map(str::to_string)),
+        ];
+        writeln!(s, "            element \"{}\" {{", kind.name()).unwrap();
+        for (name, value) in properties.iter() {
+            if let Some(value) = value {
+                writeln!(s, "                {} {}", name, value).unwrap();
+            }
+        }
+        s.push_str("            }\n");
+    }
+    s.push_str("        }\n    }\n}\n");
+    s
+}
+
+/// Writes an element for an application component, tagged with its kind and with its
+/// responsibilities after its description, up to any block of nested elements.
+fn element(
+    s: &mut String,
+    indent: &str,
+    id: &str,
+    keyword: &str,
+    component: &Component,
+    responsibilities: &[&Component],
+) {
+    let mut description = component.description.clone().unwrap_or_default();
+    if !responsibilities.is_empty() {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        let labels = responsibilities
+            .iter()
+            .map(|r| r.label.as_str())
+            .collect::<Vec<_>>();
+        write!(description, "Responsible for {}.", labels.join(", ")).unwrap();
+    }
+    // Containers and components have a technology before their tags.
+    let technology = if keyword == "softwareSystem" {
+        ""
+    } else {
+        " \"\""
+    };
+    write!(
+        s,
+        "        {}{} = {} \"{}\" \"{}\"{} \"{}\"",
+        indent,
+        id,
+        keyword,
+        escape(&component.label),
+        escape(&description),
+        technology,
+        component.kind.name()
+    )
+    .unwrap();
+}
+
+/// Writes a custom element, with the kind label as its metadata.
+fn custom(s: &mut String, id: &str, component: &Component) {
+    writeln!(
+        s,
+        "        {} = element \"{}\" \"{}\" \"{}\" \"{}\"",
+        id,
+        escape(&component.label),
+        escape(component.kind.label()),
+        escape(component.description.as_deref().unwrap_or("")),
+        component.kind.name()
+    )
+    .unwrap();
+}
+
+/// Writes a Graphviz color as a Structurizr color, which names with `#`.
+fn color(value: &str) -> String {
+    if value.starts_with('#') {
+        value.to_string()
+    } else {
+        format!("#{}", value)
+    }
+}
+
+/// Escapes text for a quoted string, in which line breaks end the statement.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' | '\t' => escaped.push(' '),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::export::structurizr::{escape, workspace};
+    use crate::architecture::fixtures;
+    use crate::architecture::theme::Theme;
+    use crate::architecture::{ComponentKind, RelationKind};
+
+    #[test]
+    fn matches_golden_file() {
+        let content = workspace(
+            &fixtures::components(),
+            &fixtures::relations(),
+            None,
+            &Theme::togaf(),
+        );
+
+        assert_eq!(content, include_str!("golden/fixtures.dsl"));
+    }
+
+    #[test]
+    fn nests_components_in_named_system() {
+        let mut components = fixtures::components();
+        components.push(fixtures::component(
+            "knowledge-server",
+            "knowledge \"server\"",
+            ComponentKind::PhysicalApplicationComponent,
+        ));
+        let mut relations = fixtures::relations();
+        relations.push(fixtures::relation(
+            "knowledge-server",
+            RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent,
+            "knowledge-application-service-component",
+        ));
+
+        let content = workspace(&components, &relations, Some("Tooling"), &Theme::togaf());
+
+        assert!(content.contains(
+            "        system = softwareSystem \"Tooling\" {\n            \
+             knowledge_application_service_component = container"
+        ));
+        assert!(content.contains(
+            "knowledge_server = component \"knowledge \\\"server\\\"\" \"\" \"\" \
+             \"PhysicalApplicationComponent\"\n"
+        ));
+        assert!(content.contains("container system \"system_containers\" {"));
+        assert!(content.contains(
+            "component knowledge_application_service_component \
+             \"knowledge_application_service_component_components\" {\n            \
+             include * knowledge_service architecture_service\n"
+        ));
+        assert_eq!(escape("a\\b\nc"), "a\\\\b c");
+    }
+}
@@ -0,0 +1,129 @@
+//! The example architecture from `architecture.ttl`, for use in tests.
+
+use super::{Component, ComponentId, ComponentKind, Relation, RelationKind};
+
+const BASE: &str = "urn:uuid:907d6137-14d5-4ef8-93bf-e967bc558fee";
+pub const GRAPH: &str = "http://localhost:3030/architecture/data/architecture.ttl";
+
+pub fn id(fragment: &str) -> ComponentId {
+    ComponentId {
+        value: format!("{}#{}", BASE, fragment),
+    }
+}
+
+pub fn component(fragment: &str, label: &str, kind: ComponentKind) -> Component {
+    Component {
+        id: id(fragment),
+        label: label.to_string(),
+        description: None,
+        kind,
+        graph: GRAPH.to_string(),
+    }
+}
+
+pub fn relation(from: &str, kind: RelationKind, to: &str) -> Relation {
+    Relation {
+        from: id(from),
+        to: id(to),
+        kind,
+    }
+}
+
+pub fn components() -> Vec<Component> {
+    vec![
+        Component {
+            description: Some("Management of models representing knowledge.".to_string()),
+            ..component(
+                "knowledge-management",
+                "knowledge management",
+                ComponentKind::Function,
+            )
+        },
+        component(
+            "knowledge-service",
+            "knowledge service",
+            ComponentKind::BusinessService,
+        ),
+        component(
+            "knowledge-application-service",
+            "knowledge application service",
+            ComponentKind::InformationSystemService,
+        ),
+        component(
+            "knowledge-application-service-component",
+            "knowledge application service component",
+            ComponentKind::LogicalApplicationComponent,
+        ),
+        Component {
+            description: Some(
+                "Structure of components, relationships, principles, and guidelines for design."
+                    .to_string(),
+            ),
+            ..component(
+                "architecture-management",
+                "architecture management",
+                ComponentKind::Function,
+            )
+        },
+        component(
+            "architecture-service",
+            "architecture service",
+            ComponentKind::BusinessService,
+        ),
+        component(
+            "architecture-application-service",
+            "architecture application service",
+            ComponentKind::InformationSystemService,
+        ),
+        component(
+            "architecture-modelling",
+            "architecture modelling",
+            ComponentKind::Process,
+        ),
+    ]
+}
+
+pub fn relations() -> Vec<Relation> {
+    vec![
+        relation(
+            "knowledge-service",
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+            "knowledge-management",
+        ),
+        relation(
+            "knowledge-application-service",
+            RelationKind::InformationSystemServiceRealizesBusinessService,
+            "knowledge-service",
+        ),
+        relation(
+            "knowledge-application-service-component",
+            RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+            "knowledge-application-service",
+        ),
+        relation(
+            "architecture-management",
+            RelationKind::FunctionIsRealizedByAndOrSupportsProcess,
+            "architecture-modelling",
+        ),
+        relation(
+            "architecture-service",
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+            "architecture-management",
+        ),
+        relation(
+            "architecture-application-service",
+            RelationKind::InformationSystemServiceRealizesBusinessService,
+            "architecture-service",
+        ),
+        relation(
+            "architecture-application-service",
+            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+            "knowledge-application-service-component",
+        ),
+        relation(
+            "architecture-modelling",
+            RelationKind::ProcessDecomposesAndOrOrchestratesFunction,
+            "knowledge-management",
+        ),
+    ]
+}
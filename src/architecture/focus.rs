@@ -0,0 +1,220 @@
+//! Diagrams of the neighbourhood of a single component, for design reviews.
+
+use super::visualization::{visualize, Options, Visualization};
+use super::{file_names, Component, ComponentId, Relation};
+use std::collections::{HashMap, VecDeque};
+
+/// Which relations to follow from the focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards the components that relate to the focus.
+    Upstream,
+    /// Towards the components that the focus relates to.
+    Downstream,
+    Both,
+}
+
+impl Direction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Upstream => "upstream",
+            Direction::Downstream => "downstream",
+            Direction::Both => "both",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Direction> {
+        [Direction::Upstream, Direction::Downstream, Direction::Both]
+            .iter()
+            .copied()
+            .find(|d| d.name() == name)
+    }
+}
+
+/// A component with everything within `depth` relations of it in the given direction.
+#[derive(Debug, Clone)]
+pub struct Focus {
+    pub component: ComponentId,
+    pub depth: usize,
+    pub direction: Direction,
+}
+
+impl Focus {
+    /// Selects the neighbourhood and all relations between the components in it.
+    pub fn select(
+        &self,
+        components: &[Component],
+        relations: &[Relation],
+    ) -> (Vec<Component>, Vec<Relation>) {
+        let mut distances: HashMap<&ComponentId, usize> = HashMap::new();
+        distances.insert(&self.component, 0);
+        let mut queue = VecDeque::new();
+        queue.push_back(&self.component);
+        while let Some(id) = queue.pop_front() {
+            let distance = distances[id];
+            if distance == self.depth {
+                continue;
+            }
+            for r in relations.iter() {
+                let next = match self.direction {
+                    Direction::Downstream if &r.from == id => &r.to,
+                    Direction::Upstream if &r.to == id => &r.from,
+                    Direction::Both if &r.from == id => &r.to,
+                    Direction::Both if &r.to == id => &r.from,
+                    _ => continue,
+                };
+                if !distances.contains_key(next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let components = components
+            .iter()
+            .filter(|c| distances.contains_key(&c.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let relations = relations
+            .iter()
+            .filter(|r| distances.contains_key(&r.from) && distances.contains_key(&r.to))
+            .cloned()
+            .collect::<Vec<_>>();
+        (components, relations)
+    }
+
+    /// Renders the neighbourhood with the focus highlighted, titled after the focus.
+    pub fn visualization(
+        &self,
+        components: &[Component],
+        relations: &[Relation],
+        options: &Options,
+    ) -> Visualization {
+        let (selected, selected_relations) = self.select(components, relations);
+        let label = components
+            .iter()
+            .find(|c| c.id == self.component)
+            .map_or_else(|| self.component.value.to_string(), |c| c.label.to_string());
+        let mut options = options.clone();
+        options.title = Some(format!(
+            "{}\nwithin {} {}, {}",
+            label,
+            self.depth,
+            if self.depth == 1 {
+                "relation"
+            } else {
+                "relations"
+            },
+            match self.direction {
+                Direction::Both => "both directions",
+                d => d.name(),
+            }
+        ));
+        options.highlighted.insert(self.component.clone());
+        visualize(&selected, &selected_relations, &options)
+    }
+}
+
+/// Renders a focused diagram for each component, with a distinct file name for it.
+pub fn focus_each(
+    components: &[Component],
+    relations: &[Relation],
+    depth: usize,
+    direction: Direction,
+    options: &Options,
+) -> Vec<(String, Visualization)> {
+    let names = file_names(components);
+    components
+        .iter()
+        .map(|c| {
+            let focus = Focus {
+                component: c.id.clone(),
+                depth,
+                direction,
+            };
+            let visualization = focus.visualization(components, relations, options);
+            (names[&c.id].to_string(), visualization)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::fixtures;
+    use crate::architecture::focus::{focus_each, Direction, Focus};
+    use crate::architecture::visualization::Options;
+
+    fn labels(focus: &Focus) -> Vec<String> {
+        let (components, _) = focus.select(&fixtures::components(), &fixtures::relations());
+        let mut labels = components.into_iter().map(|c| c.label).collect::<Vec<_>>();
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn selects_within_depth_and_direction() {
+        let focus = |depth, direction| Focus {
+            component: fixtures::id("knowledge-service"),
+            depth,
+            direction,
+        };
+
+        assert_eq!(
+            labels(&focus(1, Direction::Downstream)),
+            ["knowledge management", "knowledge service"]
+        );
+        assert_eq!(
+            labels(&focus(2, Direction::Upstream)),
+            [
+                "knowledge application service",
+                "knowledge application service component",
+                "knowledge service"
+            ]
+        );
+        assert_eq!(labels(&focus(2, Direction::Both)).len(), 5);
+        assert_eq!(labels(&focus(0, Direction::Both)), ["knowledge service"]);
+    }
+
+    #[test]
+    fn highlights_focus_in_each_diagram() {
+        let components = fixtures::components();
+        let diagrams = focus_each(
+            &components,
+            &fixtures::relations(),
+            1,
+            Direction::Both,
+            &Options::default(),
+        );
+
+        assert_eq!(diagrams.len(), components.len());
+        assert!(diagrams
+            .iter()
+            .all(|(_, v)| v.graphviz_content.matches("penwidth=\"3\"").count() == 1));
+        assert_eq!(diagrams[0].0, "knowledge-management");
+        assert!(diagrams[0]
+            .1
+            .graphviz_content
+            .contains("label=\"knowledge management\\nwithin 1 relation, both directions\""));
+    }
+
+    #[test]
+    fn names_files_distinctly() {
+        let mut components = fixtures::components()[..3].to_vec();
+        components[1].id = fixtures::id("_");
+        components[2].id.value = components[0].id.value.replace('#', "/other#");
+
+        let names = focus_each(&components, &[], 0, Direction::Both, &Options::default())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            [
+                "knowledge-management",
+                "component",
+                "knowledge-management-2"
+            ]
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! ArchiMate models in the Open Exchange Format, as written by Archi and other ArchiMate tools.
+//!
+//! Elements and relationships map to the TOGAF kinds that [`export::archimate`] maps to them.
+//! Each component has the IRI in its IRI property if it was exported by this tool, or else an
+//! IRI derived from the identifiers of the model and the element, which ArchiMate tools keep
+//! stable.
+//!
+//! [`export::archimate`]: crate::architecture::export::archimate
+
+use crate::architecture::export::archimate::{
+    element_type, relationship_type, CLASS_PROPERTY, IRI_PROPERTY, RELATION_PROPERTY,
+};
+use crate::architecture::{Component, ComponentId, ComponentKind, Relation, RelationKind};
+use std::collections::HashMap;
+use std::fmt;
+
+/// An element or relationship that was not imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unmapped {
+    pub identifier: String,
+    /// The ArchiMate type, such as `Node`.
+    pub archimate_type: String,
+    pub name: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for Unmapped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.archimate_type, self.identifier)?;
+        if let Some(name) = &self.name {
+            write!(f, " \"{}\"", name)?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+#[derive(Debug)]
+pub struct Import {
+    pub components: Vec<Component>,
+    pub relations: Vec<Relation>,
+    pub unmapped: Vec<Unmapped>,
+}
+
+impl Import {
+    pub fn to_turtle(&self) -> String {
+        super::turtle(&self.components, &self.relations)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Xml(roxmltree::Error),
+    /// The document is not an exchange model, but has the given root element.
+    NotAModel(String),
+    /// An element lacks an attribute that the format requires.
+    MissingAttribute(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Xml(e) => write!(f, "invalid XML: {}", e),
+            Error::NotAModel(root) => write!(f, "expected an ArchiMate model, not <{}>", root),
+            Error::MissingAttribute(name) => write!(f, "missing attribute {}", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reads components and relations from an exchange model, as defined in the named graph.
+pub fn import(xml: &str, graph: &str) -> Result<Import, Error> {
+    let document = roxmltree::Document::parse(xml).map_err(Error::Xml)?;
+    let model = document.root_element();
+    if model.tag_name().name() != "model" {
+        return Err(Error::NotAModel(model.tag_name().name().to_string()));
+    }
+    let model_identifier = identifier(&model)?;
+
+    let mut unmapped = vec![];
+    let mut components = vec![];
+    let mut kinds = HashMap::new();
+    for element in children(&model, "elements", "element") {
+        let identifier = identifier(&element)?;
+        let archimate_type = archimate_type(&element)?;
+        let properties = properties(&element);
+        let name = text(&element, "name");
+        let kind = match properties.get(CLASS_PROPERTY.0) {
+            Some(class) => ComponentKind::from_iri(class),
+            None => ComponentKind::ALL
+                .iter()
+                .copied()
+                .find(|k| element_type(*k) == archimate_type),
+        };
+        let kind = match kind {
+            Some(kind) => kind,
+            None => {
+                unmapped.push(Unmapped {
+                    identifier,
+                    archimate_type: archimate_type.to_string(),
+                    name,
+                    reason: "no TOGAF kind corresponds to this type".to_string(),
+                });
+                continue;
+            }
+        };
+        let iri = properties.get(IRI_PROPERTY.0).map_or_else(
+            || format!("urn:archimate:{}:{}", model_identifier, identifier),
+            |iri| iri.to_string(),
+        );
+        let id = ComponentId { value: iri };
+        kinds.insert(identifier.to_string(), (id.clone(), kind));
+        components.push(Component {
+            label: name.unwrap_or(identifier),
+            description: text(&element, "documentation"),
+            id,
+            kind,
+            graph: graph.to_string(),
+        });
+    }
+
+    let mut relations = vec![];
+    for relationship in children(&model, "relationships", "relationship") {
+        let identifier = identifier(&relationship)?;
+        let archimate_type = archimate_type(&relationship)?;
+        let properties = properties(&relationship);
+        let name = text(&relationship, "name");
+        let mut unmapped_because = |reason: &str| {
+            unmapped.push(Unmapped {
+                identifier: identifier.to_string(),
+                archimate_type: archimate_type.to_string(),
+                name: name.clone(),
+                reason: reason.to_string(),
+            })
+        };
+        let ends = (
+            relationship.attribute("source").and_then(|s| kinds.get(s)),
+            relationship.attribute("target").and_then(|t| kinds.get(t)),
+        );
+        let (source, target) = match ends {
+            (Some(source), Some(target)) => (source, target),
+            _ => {
+                unmapped_because("relates an element that was not imported");
+                continue;
+            }
+        };
+        let kind = match properties.get(RELATION_PROPERTY.0) {
+            Some(property) => RelationKind::from_iri(property),
+            None => RelationKind::ALL.iter().copied().find(|k| {
+                let (t, reversed) = relationship_type(*k);
+                let (from, to) = k.ends();
+                t == archimate_type
+                    && if reversed {
+                        (to, from) == (source.1, target.1)
+                    } else {
+                        (from, to) == (source.1, target.1)
+                    }
+            }),
+        };
+        let kind = match kind {
+            Some(kind) => kind,
+            None => {
+                unmapped_because(&format!(
+                    "no TOGAF relation corresponds to this type from {} to {}",
+                    source.1.label(),
+                    target.1.label()
+                ));
+                continue;
+            }
+        };
+        let (from, to) = if relationship_type(kind).1 {
+            (target, source)
+        } else {
+            (source, target)
+        };
+        relations.push(Relation {
+            from: from.0.clone(),
+            to: to.0.clone(),
+            kind,
+        });
+    }
+
+    Ok(Import {
+        components,
+        relations,
+        unmapped,
+    })
+}
+
+fn identifier(node: &roxmltree::Node) -> Result<String, Error> {
+    node.attribute("identifier")
+        .map(str::to_string)
+        .ok_or(Error::MissingAttribute("identifier"))
+}
+
+/// The type, such as `BusinessService`, from the `xsi:type` attribute.
+fn archimate_type<'a>(node: &roxmltree::Node<'a, '_>) -> Result<&'a str, Error> {
+    node.attributes()
+        .iter()
+        .find(|a| a.name() == "type")
+        .map(|a| a.value())
+        .ok_or(Error::MissingAttribute("xsi:type"))
+}
+
+/// The elements with the given name in the container with the given name.
+fn children<'a, 'input>(
+    model: &roxmltree::Node<'a, 'input>,
+    container: &'static str,
+    name: &'static str,
+) -> Vec<roxmltree::Node<'a, 'input>> {
+    model
+        .children()
+        .filter(|c| c.tag_name().name() == container)
+        .flat_map(|c| c.children())
+        .filter(|c| c.tag_name().name() == name)
+        .collect()
+}
+
+/// The text of the first child element with the given name, such as of the first language.
+fn text(node: &roxmltree::Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|c| c.tag_name().name() == name)
+        .and_then(|c| c.text())
+        .map(str::to_string)
+}
+
+/// The values of the properties by property definition.
+fn properties(node: &roxmltree::Node) -> HashMap<String, String> {
+    node.children()
+        .filter(|c| c.tag_name().name() == "properties")
+        .flat_map(|c| c.children())
+        .filter_map(|p| {
+            let definition = p.attribute("propertyDefinitionRef")?;
+            Some((definition.to_string(), text(&p, "value")?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::export::archimate::exchange;
+    use crate::architecture::fixtures;
+    use crate::architecture::import::archimate::{import, Error};
+    use crate::architecture::visualization::Options;
+    use crate::architecture::ComponentKind;
+
+    #[test]
+    fn restores_exported_architecture() {
+        let components = fixtures::components();
+        let relations = fixtures::relations();
+        let xml = exchange(&components, &relations, &Options::default());
+
+        let imported = import(&xml, fixtures::GRAPH).unwrap();
+
+        assert!(imported.unmapped.is_empty());
+        let summary = |c: &crate::architecture::Component| {
+            (
+                c.id.clone(),
+                c.label.to_string(),
+                c.kind,
+                c.description.clone(),
+            )
+        };
+        assert_eq!(
+            imported.components.iter().map(summary).collect::<Vec<_>>(),
+            components.iter().map(summary).collect::<Vec<_>>()
+        );
+        assert_eq!(imported.relations, relations);
+    }
+
+    #[test]
+    fn derives_iris_and_reports_unmapped() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<model xmlns="http://www.opengroup.org/xsd/archimate/3.0/"
+       xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" identifier="id-m">
+  <name xml:lang="en">Archi model</name>
+  <elements>
+    <element identifier="id-1" xsi:type="ApplicationComponent">
+      <name xml:lang="en">"Portal"</name>
+    </element>
+    <element identifier="id-2" xsi:type="ApplicationService">
+      <name xml:lang="en">Search</name>
+      <documentation xml:lang="en">Finds things.</documentation>
+    </element>
+    <element identifier="id-3" xsi:type="Node">
+      <name xml:lang="en">Server</name>
+    </element>
+  </elements>
+  <relationships>
+    <relationship identifier="id-4" source="id-1" target="id-2" xsi:type="Realization"/>
+    <relationship identifier="id-5" source="id-3" target="id-1" xsi:type="Assignment"/>
+    <relationship identifier="id-6" source="id-2" target="id-1" xsi:type="Serving"/>
+  </relationships>
+</model>"#;
+
+        let imported = import(xml, "archimate").unwrap();
+
+        assert_eq!(imported.components.len(), 2);
+        assert_eq!(imported.components[0].id.value, "urn:archimate:id-m:id-1");
+        assert_eq!(
+            imported.components[0].kind,
+            ComponentKind::LogicalApplicationComponent
+        );
+        assert_eq!(imported.relations.len(), 1);
+        assert_eq!(
+            imported
+                .unmapped
+                .iter()
+                .map(|u| u.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "Node id-3 \"Server\": no TOGAF kind corresponds to this type",
+                "Assignment id-5: relates an element that was not imported",
+                "Serving id-6: no TOGAF relation corresponds to this type from \
+                 Information system service to Logical application component"
+            ]
+        );
+        assert_eq!(
+            imported.to_turtle().lines().skip(3).collect::<Vec<_>>(),
+            [
+                "<urn:archimate:id-m:id-1>",
+                "  a togaf:LogicalApplicationComponent ;",
+                "  rdfs:label \"\\\"Portal\\\"\" ;",
+                "  togaf:logicalApplicationComponentImplementsInformationSystemService <urn:archimate:id-m:id-2> .",
+                "",
+                "<urn:archimate:id-m:id-2>",
+                "  a togaf:InformationSystemService ;",
+                "  rdfs:label \"Search\" ;",
+                "  rdfs:comment \"Finds things.\" .",
+            ]
+        );
+        assert!(matches!(import("<svg/>", ""), Err(Error::NotAModel(_))));
+    }
+}
@@ -0,0 +1,63 @@
+//! Imports of models from other modelling tools, as components and relations that can be
+//! written as TOGAF-typed Turtle.
+
+use super::{Component, Relation, TOGAF};
+use std::fmt::Write;
+
+pub mod archimate;
+
+/// Writes Turtle like `architecture.ttl`, with the relations of each component in its
+/// description.
+pub fn turtle(components: &[Component], relations: &[Relation]) -> String {
+    let mut s = format!(
+        "@prefix togaf: <{}> .\n@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .\n",
+        TOGAF
+    );
+    for c in components.iter() {
+        write!(s, "\n{}\n  a togaf:{} ;\n", iri(&c.id.value), c.kind.name()).unwrap();
+        let mut predicates = vec![format!("rdfs:label {}", string(&c.label))];
+        if let Some(d) = &c.description {
+            predicates.push(format!("rdfs:comment {}", string(d)));
+        }
+        for r in relations.iter().filter(|r| r.from == c.id) {
+            predicates.push(format!("togaf:{} {}", r.kind.name(), iri(&r.to.value)));
+        }
+        writeln!(s, "  {} .", predicates.join(" ;\n  ")).unwrap();
+    }
+    s
+}
+
+/// Writes an IRI reference, percent-encoding the characters that Turtle does not allow in one.
+fn iri(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('<');
+    for c in value.chars() {
+        match c {
+            '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => {
+                write!(escaped, "%{:02X}", c as u32).unwrap()
+            }
+            c if c <= ' ' => write!(escaped, "%{:02X}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('>');
+    escaped
+}
+
+/// Writes a string literal.
+fn string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
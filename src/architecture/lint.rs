@@ -0,0 +1,402 @@
+//! Rule-based checks of the architecture as it was loaded, statement by statement.
+
+use super::{view, Statement, TOGAF};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+const RDFS_COMMENT: &str = "http://www.w3.org/2000/01/rdf-schema#comment";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rule {
+    MissingLabel,
+    MissingComment,
+    Orphan,
+    DuplicateLabel,
+    UndefinedReference,
+    UnknownType,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [
+        Rule::MissingLabel,
+        Rule::MissingComment,
+        Rule::Orphan,
+        Rule::DuplicateLabel,
+        Rule::UndefinedReference,
+        Rule::UnknownType,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::MissingLabel => "missing-label",
+            Rule::MissingComment => "missing-comment",
+            Rule::Orphan => "orphan",
+            Rule::DuplicateLabel => "duplicate-label",
+            Rule::UndefinedReference => "undefined-reference",
+            Rule::UnknownType => "unknown-type",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Rule> {
+        Rule::ALL.iter().copied().find(|r| r.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Off,
+}
+
+impl Severity {
+    fn from_name(name: &str) -> Option<Severity> {
+        match name {
+            "error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "off" => Some(Severity::Off),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Off => "off",
+        })
+    }
+}
+
+/// Which rules to apply with which severity, and which findings to allow.
+#[derive(Debug)]
+pub struct Config {
+    pub severities: HashMap<Rule, Severity>,
+    /// Pairs of rule and component IRI for findings to ignore.
+    pub allowed: HashSet<(Rule, String)>,
+}
+
+impl Default for Config {
+    /// Reports missing documentation, orphans and duplicates as style warnings, and anything
+    /// else as an error.
+    fn default() -> Self {
+        let severities = Rule::ALL
+            .iter()
+            .map(|r| {
+                (
+                    *r,
+                    match r {
+                        Rule::MissingComment | Rule::Orphan | Rule::DuplicateLabel => {
+                            Severity::Warning
+                        }
+                        _ => Severity::Error,
+                    },
+                )
+            })
+            .collect();
+        Config {
+            severities,
+            allowed: HashSet::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads an allow-list file on top of the defaults. Each line either allows a finding, as in
+    /// `missing-comment urn:example#component`, or sets a severity, as in `orphan = off`.
+    /// Lines starting with `#` are comments.
+    pub fn parse(content: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let error = || {
+                format!(
+                    "Invalid lint configuration on line {}: {}",
+                    number + 1,
+                    line
+                )
+            };
+            let rule = Rule::from_name(words[0]).ok_or_else(error)?;
+            match words[1..] {
+                ["=", severity] => {
+                    let severity = Severity::from_name(severity).ok_or_else(error)?;
+                    config.severities.insert(rule, severity);
+                }
+                [component] => {
+                    let component = component.trim_start_matches('<').trim_end_matches('>');
+                    config.allowed.insert((rule, component.to_string()));
+                }
+                _ => return Err(error()),
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub component: String,
+    pub graph: String,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] <{}> in <{}>: {}",
+            self.severity,
+            self.rule.name(),
+            self.component,
+            self.graph,
+            self.message
+        )
+    }
+}
+
+pub fn has_errors(findings: &[Finding]) -> bool {
+    findings.iter().any(|f| f.severity == Severity::Error)
+}
+
+/// Checks every typed subject in `statements` as a component, except for [`view`] definitions.
+/// `classes` are the classes that the ontology defines.
+pub fn lint(statements: &[Statement], classes: &[String], config: &Config) -> Vec<Finding> {
+    let view_class = format!("{}View", view::NAMESPACE);
+    let views = statements
+        .iter()
+        .filter(|s| s.predicate == RDF_TYPE)
+        .filter(|s| match &s.object {
+            rdf::node::Node::UriNode { uri } => uri.to_string() == &view_class,
+            _ => false,
+        })
+        .map(|s| s.subject.as_str())
+        .collect::<HashSet<_>>();
+    let statements = statements
+        .iter()
+        .filter(|s| !views.contains(s.subject.as_str()))
+        .collect::<Vec<_>>();
+
+    let subjects = statements
+        .iter()
+        .map(|s| s.subject.as_str())
+        .collect::<HashSet<_>>();
+    let classes = classes.iter().map(|c| c.as_str()).collect::<HashSet<_>>();
+
+    // Components with the graph in which they are typed.
+    let mut components = BTreeMap::new();
+    for s in statements.iter().filter(|s| s.predicate == RDF_TYPE) {
+        components
+            .entry(s.subject.as_str())
+            .or_insert_with(|| s.graph.as_str());
+    }
+
+    let mut labels: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut commented = HashSet::new();
+    let mut related = HashSet::new();
+    let mut findings = vec![];
+    let mut report = |rule: Rule, component: &str, graph: &str, message: String| {
+        let severity = config
+            .severities
+            .get(&rule)
+            .copied()
+            .unwrap_or(Severity::Error);
+        if severity != Severity::Off && !config.allowed.contains(&(rule, component.to_string())) {
+            findings.push(Finding {
+                rule,
+                severity,
+                component: component.to_string(),
+                graph: graph.to_string(),
+                message,
+            });
+        }
+    };
+
+    for s in statements.iter() {
+        match (s.predicate.as_str(), &s.object) {
+            (RDFS_LABEL, rdf::node::Node::LiteralNode { literal, .. }) => {
+                labels.entry(s.subject.as_str()).or_default().push(literal);
+            }
+            (RDFS_COMMENT, _) => {
+                commented.insert(s.subject.as_str());
+            }
+            (RDF_TYPE, rdf::node::Node::UriNode { uri })
+                if !classes.contains(uri.to_string().as_str()) =>
+            {
+                report(
+                    Rule::UnknownType,
+                    &s.subject,
+                    &s.graph,
+                    format!("type <{}> is not a TOGAF class", uri.to_string()),
+                );
+            }
+            (RDF_TYPE, _) => {}
+            (predicate, rdf::node::Node::UriNode { uri }) => {
+                let object = uri.to_string().as_str();
+                if components.contains_key(object) {
+                    related.insert(s.subject.as_str());
+                    related.insert(object);
+                }
+                if predicate.starts_with(TOGAF) && !subjects.contains(object) {
+                    report(
+                        Rule::UndefinedReference,
+                        &s.subject,
+                        &s.graph,
+                        format!("<{}> refers to undefined <{}>", predicate, object),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut by_label: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (component, graph) in components.iter() {
+        match labels.get(component) {
+            None => report(
+                Rule::MissingLabel,
+                component,
+                graph,
+                "no rdfs:label".to_string(),
+            ),
+            Some(ls) => {
+                for label in ls.iter() {
+                    by_label.entry(label.trim()).or_default().insert(component);
+                }
+            }
+        }
+        if !commented.contains(component) {
+            report(
+                Rule::MissingComment,
+                component,
+                graph,
+                "no rdfs:comment".to_string(),
+            );
+        }
+        if !related.contains(component) {
+            report(
+                Rule::Orphan,
+                component,
+                graph,
+                "no relations to or from other components".to_string(),
+            );
+        }
+    }
+    for (label, users) in by_label.iter().filter(|(_, users)| users.len() > 1) {
+        for component in users.iter() {
+            report(
+                Rule::DuplicateLabel,
+                component,
+                components[component],
+                format!("label \"{}\" is used by {} components", label, users.len()),
+            );
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        (a.severity, a.rule, &a.component).cmp(&(b.severity, b.rule, &b.component))
+    });
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::lint::{lint, Config, Rule, Severity};
+    use crate::architecture::Statement;
+    use crate::architecture::{ComponentKind, RelationKind};
+
+    const GRAPH: &str = "urn:example:graph";
+
+    fn statement(subject: &str, predicate: &str, object: rdf::node::Node) -> Statement {
+        Statement {
+            graph: GRAPH.to_string(),
+            subject: subject.to_string(),
+            predicate: predicate.to_string(),
+            object,
+        }
+    }
+
+    fn uri(value: &str) -> rdf::node::Node {
+        rdf::node::Node::UriNode {
+            uri: rdf::uri::Uri::new(value.to_string()),
+        }
+    }
+
+    fn literal(value: &str) -> rdf::node::Node {
+        rdf::node::Node::LiteralNode {
+            literal: value.to_string(),
+            data_type: None,
+            language: None,
+        }
+    }
+
+    fn statements() -> Vec<Statement> {
+        let function = ComponentKind::Function.iri();
+        let service = ComponentKind::BusinessService.iri();
+        let provides = RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction.iri();
+        vec![
+            statement("urn:a", super::RDF_TYPE, uri(&function)),
+            statement("urn:a", super::RDFS_LABEL, literal("management")),
+            statement("urn:a", super::RDFS_COMMENT, literal("Managing.")),
+            statement("urn:b", super::RDF_TYPE, uri(&service)),
+            statement("urn:b", super::RDFS_LABEL, literal("management")),
+            statement("urn:b", &provides, uri("urn:a")),
+            statement("urn:b", &provides, uri("urn:missing")),
+            statement("urn:c", super::RDF_TYPE, uri("urn:example:Thing")),
+        ]
+    }
+
+    #[test]
+    fn reports_each_rule() {
+        let classes = vec![
+            ComponentKind::Function.iri(),
+            ComponentKind::BusinessService.iri(),
+        ];
+        let findings = lint(&statements(), &classes, &Config::default());
+
+        let found = findings
+            .iter()
+            .map(|f| (f.severity, f.rule, f.component.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Error, Rule::MissingLabel, "urn:c"),
+                (Severity::Error, Rule::UndefinedReference, "urn:b"),
+                (Severity::Error, Rule::UnknownType, "urn:c"),
+                (Severity::Warning, Rule::MissingComment, "urn:b"),
+                (Severity::Warning, Rule::MissingComment, "urn:c"),
+                (Severity::Warning, Rule::Orphan, "urn:c"),
+                (Severity::Warning, Rule::DuplicateLabel, "urn:a"),
+                (Severity::Warning, Rule::DuplicateLabel, "urn:b"),
+            ]
+        );
+        assert!(findings.iter().all(|f| f.graph == GRAPH));
+    }
+
+    #[test]
+    fn applies_allow_list() {
+        let config = Config::parse(
+            "# Style is not a concern here\nmissing-comment = off\norphan <urn:c>\nunknown-type urn:c\n",
+        )
+        .unwrap();
+        let findings = lint(&statements(), &[], &config);
+
+        assert!(findings
+            .iter()
+            .all(|f| f.rule != Rule::MissingComment && f.rule != Rule::Orphan));
+        assert!(findings
+            .iter()
+            .all(|f| f.rule != Rule::UnknownType || f.component != "urn:c"));
+        assert!(Config::parse("orphan = fatal").is_err());
+    }
+}
@@ -1,89 +1,423 @@
 use super::knowledge;
 use crate::knowledge::Graph;
 use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug)]
+pub mod catalog;
+pub mod coverage;
+pub mod cycles;
+pub mod description;
+pub mod diff;
+pub mod explorer;
+pub mod export;
+#[cfg(test)]
+mod fixtures;
+pub mod focus;
+pub mod import;
+pub mod lint;
+pub mod partition;
+pub mod report;
+pub mod site;
+pub mod theme;
+pub mod traceability;
+pub mod view;
+pub mod visualization;
+
+pub use visualization::{visualization, Visualization};
+
+const TOGAF: &str =
+    "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#";
+
+/// The architecture domains, in order from business to technology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchitectureDomain {
+    Business,
+    Data,
+    Application,
+    Technology,
+}
+
+impl ArchitectureDomain {
+    pub const ALL: [ArchitectureDomain; 4] = [
+        ArchitectureDomain::Business,
+        ArchitectureDomain::Data,
+        ArchitectureDomain::Application,
+        ArchitectureDomain::Technology,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArchitectureDomain::Business => "business",
+            ArchitectureDomain::Data => "data",
+            ArchitectureDomain::Application => "application",
+            ArchitectureDomain::Technology => "technology",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArchitectureDomain::Business => "Business architecture",
+            ArchitectureDomain::Data => "Data architecture",
+            ArchitectureDomain::Application => "Application architecture",
+            ArchitectureDomain::Technology => "Technology architecture",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ArchitectureDomain> {
+        ArchitectureDomain::ALL
+            .iter()
+            .copied()
+            .find(|d| d.name() == name)
+    }
+}
+
+/// The TOGAF class of a component. Deserializes from the local name of the class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum ComponentKind {
     BusinessService,
     Function,
     InformationSystemService,
     Process,
+    LogicalApplicationComponent,
+    PhysicalApplicationComponent,
+    DataEntity,
+    PlatformService,
 }
 
-#[derive(Debug)]
+impl ComponentKind {
+    pub const ALL: [ComponentKind; 8] = [
+        ComponentKind::BusinessService,
+        ComponentKind::Function,
+        ComponentKind::InformationSystemService,
+        ComponentKind::Process,
+        ComponentKind::LogicalApplicationComponent,
+        ComponentKind::PhysicalApplicationComponent,
+        ComponentKind::DataEntity,
+        ComponentKind::PlatformService,
+    ];
+
+    /// The local name of the TOGAF class.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComponentKind::BusinessService => "BusinessService",
+            ComponentKind::Function => "Function",
+            ComponentKind::InformationSystemService => "InformationSystemService",
+            ComponentKind::Process => "Process",
+            ComponentKind::LogicalApplicationComponent => "LogicalApplicationComponent",
+            ComponentKind::PhysicalApplicationComponent => "PhysicalApplicationComponent",
+            ComponentKind::DataEntity => "DataEntity",
+            ComponentKind::PlatformService => "PlatformService",
+        }
+    }
+
+    /// The domain in which the kind is defined. Information system services are part of the
+    /// application domain here, even though the ontology classifies them as business
+    /// architecture, since they describe what applications provide.
+    pub fn domain(&self) -> ArchitectureDomain {
+        match self {
+            ComponentKind::BusinessService | ComponentKind::Function | ComponentKind::Process => {
+                ArchitectureDomain::Business
+            }
+            ComponentKind::InformationSystemService
+            | ComponentKind::LogicalApplicationComponent
+            | ComponentKind::PhysicalApplicationComponent => ArchitectureDomain::Application,
+            ComponentKind::DataEntity => ArchitectureDomain::Data,
+            ComponentKind::PlatformService => ArchitectureDomain::Technology,
+        }
+    }
+
+    pub fn iri(&self) -> String {
+        format!("{}{}", TOGAF, self.name())
+    }
+
+    pub fn from_iri(iri: &str) -> Option<ComponentKind> {
+        ComponentKind::ALL.iter().copied().find(|k| k.iri() == iri)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComponentKind::BusinessService => "Business service",
+            ComponentKind::Function => "Function",
+            ComponentKind::InformationSystemService => "Information system service",
+            ComponentKind::Process => "Process",
+            ComponentKind::LogicalApplicationComponent => "Logical application component",
+            ComponentKind::PhysicalApplicationComponent => "Physical application component",
+            ComponentKind::DataEntity => "Data entity",
+            ComponentKind::PlatformService => "Platform service",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ComponentId {
     pub value: String,
 }
 
+impl ComponentId {
+    /// A name for files about the component, based on the last segment or fragment of its IRI.
+    /// Distinct IRIs may have the same slug.
+    pub fn slug(&self) -> String {
+        let segment = self
+            .value
+            .rsplit(&['#', '/', ':'][..])
+            .find(|s| !s.is_empty())
+            .unwrap_or("");
+        slug(segment)
+    }
+}
+
 /// An architecture component. Of any level.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Component {
     pub id: ComponentId,
     pub label: String,
     pub description: Option<String>,
     pub kind: ComponentKind,
+    /// The named graph that defines the component's kind.
+    pub graph: String,
 }
 
-#[derive(Debug)]
+/// The TOGAF object property relating two components. Deserializes from the local name of the
+/// property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelationKind {
+    InformationSystemServiceRealizesBusinessService,
+    BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+    ProcessDecomposesAndOrOrchestratesFunction,
+    FunctionIsRealizedByAndOrSupportsProcess,
+    LogicalApplicationComponentImplementsInformationSystemService,
+    InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+    PhysicalApplicationComponentExtendsLogicalApplicationComponent,
+}
+
+impl RelationKind {
+    pub const ALL: [RelationKind; 7] = [
+        RelationKind::InformationSystemServiceRealizesBusinessService,
+        RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+        RelationKind::ProcessDecomposesAndOrOrchestratesFunction,
+        RelationKind::FunctionIsRealizedByAndOrSupportsProcess,
+        RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+        RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+        RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent,
+    ];
+
+    /// The local name of the TOGAF object property.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RelationKind::InformationSystemServiceRealizesBusinessService => {
+                "informationSystemServiceRealizesBusinessService"
+            }
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction => {
+                "businessServiceProvidesGovernedInterfaceToAccessFunction"
+            }
+            RelationKind::ProcessDecomposesAndOrOrchestratesFunction => {
+                "processDecomposesAndOrOrchestratesFunction"
+            }
+            RelationKind::FunctionIsRealizedByAndOrSupportsProcess => {
+                "functionIsRealizedByAndOrSupportsProcess"
+            }
+            RelationKind::LogicalApplicationComponentImplementsInformationSystemService => {
+                "logicalApplicationComponentImplementsInformationSystemService"
+            }
+            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent => {
+                "informationSystemServiceIsRealizedThroughLogicalApplicationComponent"
+            }
+            RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent => {
+                "physicalApplicationComponentExtendsLogicalApplicationComponent"
+            }
+        }
+    }
+
+    pub fn iri(&self) -> String {
+        format!("{}{}", TOGAF, self.name())
+    }
+
+    pub fn from_iri(iri: &str) -> Option<RelationKind> {
+        RelationKind::ALL.iter().copied().find(|k| k.iri() == iri)
+    }
+
+    pub fn from_name(name: &str) -> Option<RelationKind> {
+        RelationKind::ALL.iter().copied().find(|k| k.name() == name)
+    }
+
+    /// The kinds of the components that the relation is from and to.
+    pub fn ends(&self) -> (ComponentKind, ComponentKind) {
+        match self {
+            RelationKind::InformationSystemServiceRealizesBusinessService => (
+                ComponentKind::InformationSystemService,
+                ComponentKind::BusinessService,
+            ),
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction => {
+                (ComponentKind::BusinessService, ComponentKind::Function)
+            }
+            RelationKind::ProcessDecomposesAndOrOrchestratesFunction => {
+                (ComponentKind::Process, ComponentKind::Function)
+            }
+            RelationKind::FunctionIsRealizedByAndOrSupportsProcess => {
+                (ComponentKind::Function, ComponentKind::Process)
+            }
+            RelationKind::LogicalApplicationComponentImplementsInformationSystemService => (
+                ComponentKind::LogicalApplicationComponent,
+                ComponentKind::InformationSystemService,
+            ),
+            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent => (
+                ComponentKind::InformationSystemService,
+                ComponentKind::LogicalApplicationComponent,
+            ),
+            RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent => (
+                ComponentKind::PhysicalApplicationComponent,
+                ComponentKind::LogicalApplicationComponent,
+            ),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RelationKind::InformationSystemServiceRealizesBusinessService => "realizes",
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction => {
+                "provides governed interface to access"
+            }
+            RelationKind::ProcessDecomposesAndOrOrchestratesFunction => {
+                "orchestrates and/or decomposes"
+            }
+            RelationKind::FunctionIsRealizedByAndOrSupportsProcess => "supports or is realized by",
+            RelationKind::LogicalApplicationComponentImplementsInformationSystemService => {
+                "implements"
+            }
+            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent => {
+                "is realized through"
+            }
+            RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent => {
+                "extends"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Relation {
     pub from: ComponentId,
     pub to: ComponentId,
-    pub label: String,
+    pub kind: RelationKind,
 }
 
+/// A statement from a named graph other than the ontology.
 #[derive(Debug)]
-pub struct Visualization {
-    pub graphviz_content: String,
+pub struct Statement {
+    pub graph: String,
+    pub subject: String,
+    pub predicate: String,
+    pub object: rdf::node::Node,
 }
 
-#[async_trait]
-pub trait ArchitectureService {
-    async fn components(&self) -> Vec<Component>;
-    async fn relations(&self) -> Vec<Relation>;
-    async fn component_ids(&self) -> Vec<ComponentId>;
-    async fn describe(&self, component_id: &ComponentId) -> super::knowledge::Graph;
+/// Lowercases words and joins them with hyphens.
+fn slug(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
-pub fn visualization(components: Vec<Component>, relations: Vec<Relation>) -> Visualization {
-    let mut s = "digraph components_relations {\n".to_string();
-
+/// A distinct file name for each component, based on its slug and numbered where slugs collide.
+/// Components with an empty slug are named `component`.
+pub fn file_names(components: &[Component]) -> HashMap<ComponentId, String> {
+    let mut taken = HashSet::new();
+    let mut names = HashMap::new();
     for c in components.iter() {
-        s.push('"');
-        s.push_str(c.id.value.replace('"', "\\\"").as_str());
-        s.push_str("\" [shape=plain,style=filled,label=<<B>");
-        s.push_str(c.label.replace('"', "\\\"").as_str());
-        s.push_str("</B><BR/>[");
-        s.push_str(match c.kind {
-            ComponentKind::BusinessService => "Business service",
-            ComponentKind::Function => "Function",
-            ComponentKind::InformationSystemService => "Information system service",
-            ComponentKind::Process => "Process",
-        });
-        s.push_str("]");
-        match &c.description {
-            None => {}
-            Some(d) => {
-                s.push_str("<BR/><BR/>");
-                s.push_str(d.replace('"', "\\\"").as_str());
-            }
+        if !names.contains_key(&c.id) {
+            let name = distinct(&c.id.slug(), "component", &mut taken);
+            names.insert(c.id.clone(), name);
         }
-        s.push_str(">]\n");
     }
+    names
+}
 
-    for r in relations.iter() {
-        s.push('"');
-        s.push_str(r.from.value.replace('"', "\\\"").as_str());
-        s.push_str("\" -> \"");
-        s.push_str(r.to.value.replace('"', "\\\"").as_str());
-        s.push_str("\" [label=\"");
-        s.push_str(r.label.replace('"', "\\\"").as_str());
-        s.push_str("\"]\n");
+/// The name, or `fallback` if it is empty, numbered if already taken, and then taken.
+fn distinct(name: &str, fallback: &str, taken: &mut HashSet<String>) -> String {
+    let base = if name.is_empty() { fallback } else { name };
+    let mut name = base.to_string();
+    let mut n = 1;
+    while !taken.insert(name.to_string()) {
+        n += 1;
+        name = format!("{}-{}", base, n);
     }
+    name
+}
+
+/// Checks that `s` is an absolute IRI that can be written between angle brackets in SPARQL: a
+/// scheme and a colon, followed by no spaces, control characters or any of `<>"{}|^`\`.
+fn absolute_iri(s: &str) -> Result<&str, String> {
+    let scheme = s.split(':').next().unwrap_or_default();
+    let valid_scheme = s.contains(':')
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+    if !valid_scheme {
+        Err(format!("not an absolute IRI: {}", s))
+    } else if let Some(c) = s
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control() || "<>\"{}|^`\\".contains(*c))
+    {
+        Err(format!("invalid character {:?} in IRI {}", c, s))
+    } else {
+        Ok(s)
+    }
+}
+
+/// Renders IRIs as the content of a SPARQL `VALUES` block.
+fn values(iris: impl Iterator<Item = String>) -> String {
+    iris.map(|iri| format!("<{}>", iri))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[async_trait]
+pub trait ArchitectureService {
+    async fn components(&self) -> Vec<Component>;
+    async fn relations(&self) -> Vec<Relation>;
+    async fn component_ids(&self) -> Vec<ComponentId>;
+    /// The statements about the component, with labels of their properties and values.
+    async fn describe(&self, component_id: &ComponentId) -> description::ComponentDescription;
+    /// The statements about the component as returned by a SPARQL `DESCRIBE` query.
+    async fn describe_graph(&self, component_id: &ComponentId) -> Graph;
+    /// The relations between component kinds that the ontology defines.
+    async fn expectations(&self) -> Vec<coverage::Expectation>;
+    async fn coverage(&self) -> coverage::Coverage;
+    /// The statements in all named graphs except for the ontology.
+    async fn statements(&self) -> Vec<Statement>;
+    /// The classes that the ontology defines.
+    async fn classes(&self) -> Vec<String>;
+    /// The views defined in the dataset, as described in [`view`], or an error describing an
+    /// invalid definition.
+    async fn views(&self) -> Result<Vec<view::View>, String>;
+    /// The value of `property` for each component that has one, as the label of the value if it
+    /// has a label, or an error if `property` is not an absolute IRI.
+    async fn property_values(&self, property: &str)
+        -> Result<HashMap<ComponentId, String>, String>;
+    /// The labels that the ontology defines for the supported component and relation kinds, by
+    /// IRI.
+    async fn kind_labels(&self) -> HashMap<String, String>;
+}
 
-    s.push_str("}");
+fn iri(node: Option<&rdf::node::Node>) -> String {
+    match node {
+        Some(rdf::node::Node::UriNode { uri }) => uri.to_string().to_string(),
+        n => panic!("Unexpected node {:?}", n),
+    }
+}
 
-    Visualization {
-        graphviz_content: s,
+fn component_kind(node: Option<&rdf::node::Node>) -> ComponentKind {
+    match node {
+        Some(rdf::node::Node::UriNode { uri }) => match ComponentKind::from_iri(uri.to_string()) {
+            Some(kind) => kind,
+            None => panic!("Unknown kind {}.", uri.to_string()),
+        },
+        k => panic!("Unexpected kind {:?}", k),
     }
 }
 
@@ -97,48 +431,47 @@ impl<'a, K: knowledge::KnowledgeService + 'a + std::marker::Sync> ArchitectureSe
     for DataBackedArchitectureService<'a, K>
 {
     async fn components(&self) -> Vec<Component> {
-        let query: &str = std::include_str!("components.sparql");
-        let result = &self.knowledge.select(&self.dataset, query).await;
+        let template: &str = std::include_str!("components.sparql");
+        let query = template.replace(
+            "$KINDS",
+            &values(ComponentKind::ALL.iter().map(|k| k.iri())),
+        );
+        let result = &self.knowledge.select(&self.dataset, &query).await;
 
-        assert_eq!(result.vars, ["component", "label", "description", "kind"]);
+        assert_eq!(
+            result.vars,
+            ["component", "label", "description", "kind", "graph"]
+        );
 
         result
             .bindings
             .iter()
-            .map(|record| {
-                Component {
-                    id: match record.get("component") {
-                        Some(rdf::node::Node::UriNode { uri }) => ComponentId { value: uri.to_string().to_string() },
-                        c => panic!("Unexpected component {:?}", c),
-                    },
-                    label: match record.get("label") {
-                        Some(rdf::node::Node::LiteralNode {
-                                 literal: label,
-                                 data_type: _,
-                                 language: _,
-                             }) => label.to_string(),
-                        _ => panic!("Unexpected label"),
-                    },
-                    description: match record.get("description") {
-                        Some(rdf::node::Node::LiteralNode {
-                                 literal: description,
-                                 data_type: _,
-                                 language: _,
-                             }) => Some(description.to_string()),
-                        None => None,
-                        d => panic!("Unexpected description {:?}", d),
+            .map(|record| Component {
+                id: match record.get("component") {
+                    Some(rdf::node::Node::UriNode { uri }) => ComponentId {
+                        value: uri.to_string().to_string(),
                     },
-                    kind: match record.get("kind") {
-                        Some(rdf::node::Node::UriNode { uri }) => match uri.to_string().as_str() {
-                            "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#BusinessService" => ComponentKind::BusinessService,
-                            "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#InformationSystemService" => ComponentKind::InformationSystemService,
-                            "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#Function" => ComponentKind::Function,
-                            "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#Process" => ComponentKind::Process,
-                            kind => panic!("Unknown kind {}.", kind)
-                        },
-                        _ => panic!("Unexpected kind"),
-                    },
-                }
+                    c => panic!("Unexpected component {:?}", c),
+                },
+                label: match record.get("label") {
+                    Some(rdf::node::Node::LiteralNode {
+                        literal: label,
+                        data_type: _,
+                        language: _,
+                    }) => label.to_string(),
+                    _ => panic!("Unexpected label"),
+                },
+                description: match record.get("description") {
+                    Some(rdf::node::Node::LiteralNode {
+                        literal: description,
+                        data_type: _,
+                        language: _,
+                    }) => Some(description.to_string()),
+                    None => None,
+                    d => panic!("Unexpected description {:?}", d),
+                },
+                kind: component_kind(record.get("kind")),
+                graph: iri(record.get("graph")),
             })
             .collect::<Vec<_>>()
     }
@@ -162,41 +495,216 @@ impl<'a, K: knowledge::KnowledgeService + 'a + std::marker::Sync> ArchitectureSe
     }
 
     async fn relations(&self) -> Vec<Relation> {
-        let query: &str = std::include_str!("relations.sparql");
-        let result = &self.knowledge.select(&self.dataset, query).await;
+        let template: &str = std::include_str!("relations.sparql");
+        let query = template
+            .replace(
+                "$KINDS",
+                &values(ComponentKind::ALL.iter().map(|k| k.iri())),
+            )
+            .replace(
+                "$RELATIONS",
+                &values(RelationKind::ALL.iter().map(|k| k.iri())),
+            );
+        let result = &self.knowledge.select(&self.dataset, &query).await;
 
-        assert_eq!(result.vars, ["from", "label", "to"]);
+        assert_eq!(result.vars, ["from", "kind", "to"]);
 
         result
             .bindings
             .iter()
             .map(|record| Relation {
                 from: match record.get("from") {
-                    Some(rdf::node::Node::UriNode { uri }) => ComponentId { value: uri.to_string().to_string() },
+                    Some(rdf::node::Node::UriNode { uri }) => ComponentId {
+                        value: uri.to_string().to_string(),
+                    },
                     _ => panic!("Unexpected from"),
                 },
-                label: match record.get("label") {
-                    Some(rdf::node::Node::UriNode { uri }) => match uri.to_string().as_str() {
-                        "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#informationSystemServiceRealizesBusinessService" => "realizes".to_string(),
-                        "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#businessServiceProvidesGovernedInterfaceToAccessFunction" => "provides governed interface to access".to_string(),
-                        "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#processDecomposesAndOrOrchestratesFunction" => "orchestrates and/or decomposes".to_string(),
-                        "http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#functionIsRealizedByAndOrSupportsProcess" => "supports or is realized by".to_string(),
-                        url => panic!("Unknown url {}.", url)
-                    },
-                    _ => panic!("Unexpected label"),
+                kind: match record.get("kind") {
+                    Some(rdf::node::Node::UriNode { uri }) => {
+                        match RelationKind::from_iri(uri.to_string()) {
+                            Some(kind) => kind,
+                            None => panic!("Unknown url {}.", uri.to_string()),
+                        }
+                    }
+                    _ => panic!("Unexpected kind"),
                 },
                 to: match record.get("to") {
-                    Some(rdf::node::Node::UriNode { uri }) => ComponentId { value: uri.to_string().to_string() },
+                    Some(rdf::node::Node::UriNode { uri }) => ComponentId {
+                        value: uri.to_string().to_string(),
+                    },
                     _ => panic!("Unexpected to"),
                 },
             })
             .collect::<Vec<_>>()
     }
 
-    async fn describe(&self, component_id: &ComponentId) -> Graph {
+    async fn describe(&self, component_id: &ComponentId) -> description::ComponentDescription {
+        let template: &str = std::include_str!("description.sparql");
+        let query = template.replace("$ID", component_id.value.as_str());
+        let result = self.knowledge.select(self.dataset, &query).await;
+
+        assert_eq!(
+            result.vars,
+            [
+                "direction",
+                "graph",
+                "property",
+                "propertyLabel",
+                "value",
+                "valueLabel"
+            ]
+        );
+
+        description::from_bindings(component_id, &result.bindings)
+    }
+
+    async fn describe_graph(&self, component_id: &ComponentId) -> Graph {
         let template: &str = std::include_str!("describe.sparql");
         let query = template.replace("$ID", component_id.value.as_str());
         let result = self.knowledge.describe(&self.dataset, query.as_str()).await;
         result
     }
+
+    async fn expectations(&self) -> Vec<coverage::Expectation> {
+        let template: &str = std::include_str!("expectations.sparql");
+        let query = template.replace(
+            "$KINDS",
+            &values(ComponentKind::ALL.iter().map(|k| k.iri())),
+        );
+        let result = &self.knowledge.select(self.dataset, &query).await;
+
+        assert_eq!(result.vars, ["property", "domain", "range"]);
+
+        result
+            .bindings
+            .iter()
+            .map(|record| coverage::Expectation {
+                property: match record.get("property") {
+                    Some(rdf::node::Node::UriNode { uri }) => uri.to_string().to_string(),
+                    p => panic!("Unexpected property {:?}", p),
+                },
+                domain: component_kind(record.get("domain")),
+                range: component_kind(record.get("range")),
+            })
+            .collect::<Vec<_>>()
+    }
+
+    async fn coverage(&self) -> coverage::Coverage {
+        let components = self.components().await;
+        let relations = self.relations().await;
+        let expectations = self.expectations().await;
+        coverage::coverage(&components, &relations, &expectations)
+    }
+
+    async fn statements(&self) -> Vec<Statement> {
+        let query: &str = std::include_str!("statements.sparql");
+        let result = self.knowledge.select(self.dataset, query).await;
+
+        assert_eq!(result.vars, ["graph", "subject", "predicate", "object"]);
+
+        result
+            .bindings
+            .into_iter()
+            .map(|mut record| Statement {
+                graph: iri(record.get("graph")),
+                subject: iri(record.get("subject")),
+                predicate: iri(record.get("predicate")),
+                object: record.remove("object").expect("Unexpected object"),
+            })
+            .collect::<Vec<_>>()
+    }
+
+    async fn classes(&self) -> Vec<String> {
+        let query: &str = std::include_str!("classes.sparql");
+        let result = self.knowledge.select(self.dataset, query).await;
+
+        assert_eq!(result.vars, ["class"]);
+
+        result
+            .bindings
+            .iter()
+            .map(|record| iri(record.get("class")))
+            .collect::<Vec<_>>()
+    }
+
+    async fn views(&self) -> Result<Vec<view::View>, String> {
+        let query: &str = std::include_str!("views.sparql");
+        let result = self.knowledge.select(self.dataset, query).await;
+
+        assert_eq!(result.vars, ["graph", "view", "property", "value"]);
+
+        let statements = result
+            .bindings
+            .into_iter()
+            .map(|mut record| Statement {
+                graph: iri(record.get("graph")),
+                subject: iri(record.get("view")),
+                predicate: iri(record.get("property")),
+                object: record.remove("value").expect("Unexpected value"),
+            })
+            .collect::<Vec<_>>();
+        view::from_statements(&statements)
+    }
+
+    async fn property_values(
+        &self,
+        property: &str,
+    ) -> Result<HashMap<ComponentId, String>, String> {
+        let template: &str = std::include_str!("property_values.sparql");
+        let query = template.replace("$PROPERTY", absolute_iri(property)?);
+        let result = self.knowledge.select(self.dataset, &query).await;
+
+        assert_eq!(result.vars, ["component", "value"]);
+
+        let mut values = HashMap::new();
+        for record in result.bindings.iter() {
+            let value = match record.get("value") {
+                Some(rdf::node::Node::LiteralNode { literal, .. }) => literal.to_string(),
+                v => panic!("Unexpected value {:?}", v),
+            };
+            values
+                .entry(ComponentId {
+                    value: iri(record.get("component")),
+                })
+                .or_insert(value);
+        }
+        Ok(values)
+    }
+
+    async fn kind_labels(&self) -> HashMap<String, String> {
+        let iris = ComponentKind::ALL
+            .iter()
+            .map(|k| k.iri())
+            .chain(RelationKind::ALL.iter().map(|k| k.iri()))
+            .collect::<Vec<_>>();
+        let resources = iris
+            .iter()
+            .map(|iri| knowledge::Resource::from(iri.as_str()))
+            .collect();
+        let labels = knowledge::get_labels(self.knowledge, self.dataset, resources).await;
+        iris.into_iter()
+            .filter_map(|iri| {
+                let label = labels.get(&knowledge::Resource::from(iri.as_str()))?;
+                Some((iri.clone(), label.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::absolute_iri;
+
+    #[test]
+    fn accepts_only_absolute_iris() {
+        assert!(absolute_iri("http://example.com/ownedBy").is_ok());
+        assert!(absolute_iri("urn:x-org:unit").is_ok());
+        assert_eq!(
+            absolute_iri("domian"),
+            Err("not an absolute IRI: domian".to_string())
+        );
+        assert!(absolute_iri("1http://example.com").is_err());
+        assert!(absolute_iri("http://example.com/> ?x <http://example.com/").is_err());
+        assert!(absolute_iri("http://example.com/{x}").is_err());
+    }
 }
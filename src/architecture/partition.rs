@@ -0,0 +1,304 @@
+//! Splitting of large diagrams into pages, with stubs for the relations between pages.
+
+use super::visualization::{edge, graph, Options, Visualization};
+use super::{slug, ArchitectureDomain, Component, ComponentId, Relation};
+use crate::graphviz::{self, Value};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Partitioning {
+    /// A page per architecture domain.
+    Domain,
+    /// A page per set of components that are connected by relations.
+    Connected,
+    /// Pages of at most the given number of components, keeping related components together
+    /// where possible.
+    Size(usize),
+}
+
+/// A relation with a component on another page.
+#[derive(Debug)]
+pub struct Stub {
+    pub relation: Relation,
+    pub component: Component,
+    /// The index of the page of the component.
+    pub page: usize,
+}
+
+#[derive(Debug)]
+pub struct Page {
+    pub name: String,
+    pub components: Vec<Component>,
+    /// The relations between components on this page.
+    pub relations: Vec<Relation>,
+    pub stubs: Vec<Stub>,
+}
+
+impl Page {
+    pub fn file_name(&self) -> String {
+        slug(&self.name)
+    }
+
+    /// Renders the page with a stub node for each component on another page, linking to the
+    /// SVG file of that page in the same directory.
+    pub fn visualization(&self, pages: &[Page], options: &Options) -> Visualization {
+        let mut options = options.clone();
+        options.title = Some(self.name.to_string());
+        let mut graph = graph(&self.components, &self.relations, &options);
+
+        let labels = self
+            .components
+            .iter()
+            .chain(self.stubs.iter().map(|s| &s.component))
+            .map(|c| (&c.id, c))
+            .collect::<HashMap<_, _>>();
+        let mut stubbed = HashSet::new();
+        for stub in self.stubs.iter() {
+            let page = &pages[stub.page];
+            if stubbed.insert(&stub.component.id) {
+                graph.nodes.push(graphviz::Node {
+                    id: stub.component.id.value.to_string(),
+                    attributes: vec![
+                        ("shape".to_string(), Value::text("note")),
+                        ("style".to_string(), Value::text("dashed")),
+                        (
+                            "label".to_string(),
+                            Value::Text(format!("{}\n→ {}", stub.component.label, page.name)),
+                        ),
+                        (
+                            "URL".to_string(),
+                            Value::Text(format!("{}.svg", page.file_name())),
+                        ),
+                        (
+                            "tooltip".to_string(),
+                            Value::Text(format!("Continued on {}", page.name)),
+                        ),
+                    ],
+                });
+            }
+            graph.edges.push(edge(&stub.relation, &labels, &options));
+        }
+
+        graph.into()
+    }
+}
+
+pub fn partition(
+    components: &[Component],
+    relations: &[Relation],
+    partitioning: Partitioning,
+) -> Vec<Page> {
+    let parts: Vec<(String, Vec<&Component>)> = match partitioning {
+        Partitioning::Domain => ArchitectureDomain::ALL
+            .iter()
+            .map(|d| {
+                let members = components
+                    .iter()
+                    .filter(|c| c.kind.domain() == *d)
+                    .collect::<Vec<_>>();
+                (d.label().to_string(), members)
+            })
+            .filter(|(_, members)| !members.is_empty())
+            .collect(),
+        Partitioning::Connected => connected(components, relations)
+            .into_iter()
+            .enumerate()
+            .map(|(i, members)| (format!("Part {}", i + 1), members))
+            .collect(),
+        Partitioning::Size(limit) => connected(components, relations)
+            .concat()
+            .chunks(limit.max(1))
+            .enumerate()
+            .map(|(i, members)| (format!("Part {}", i + 1), members.to_vec()))
+            .collect(),
+    };
+
+    let page_of = parts
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (_, members))| members.iter().map(move |c| (&c.id, (i, *c))))
+        .collect::<HashMap<_, _>>();
+    let mut pages = parts
+        .iter()
+        .map(|(name, members)| Page {
+            name: name.to_string(),
+            components: members.iter().map(|c| (*c).clone()).collect(),
+            relations: vec![],
+            stubs: vec![],
+        })
+        .collect::<Vec<_>>();
+    for r in relations.iter() {
+        let (from, to) = match (page_of.get(&r.from), page_of.get(&r.to)) {
+            (Some(from), Some(to)) => (from, to),
+            _ => continue,
+        };
+        if from.0 == to.0 {
+            pages[from.0].relations.push(r.clone());
+        } else {
+            pages[from.0].stubs.push(Stub {
+                relation: r.clone(),
+                component: to.1.clone(),
+                page: to.0,
+            });
+            pages[to.0].stubs.push(Stub {
+                relation: r.clone(),
+                component: from.1.clone(),
+                page: from.0,
+            });
+        }
+    }
+    pages
+}
+
+/// The sets of components that are connected by relations in either direction, each in
+/// breadth-first order from its first component.
+fn connected<'a>(components: &'a [Component], relations: &[Relation]) -> Vec<Vec<&'a Component>> {
+    let mut neighbours: HashMap<&ComponentId, Vec<&ComponentId>> = HashMap::new();
+    for r in relations.iter() {
+        neighbours.entry(&r.from).or_default().push(&r.to);
+        neighbours.entry(&r.to).or_default().push(&r.from);
+    }
+    let by_id = components
+        .iter()
+        .map(|c| (&c.id, c))
+        .collect::<HashMap<_, _>>();
+    let mut visited = HashSet::new();
+    let mut parts = vec![];
+    for c in components.iter() {
+        if !visited.insert(&c.id) {
+            continue;
+        }
+        let mut part = vec![];
+        let mut queue = VecDeque::new();
+        queue.push_back(&c.id);
+        while let Some(id) = queue.pop_front() {
+            part.push(by_id[id]);
+            for next in neighbours.get(id).into_iter().flatten() {
+                if by_id.contains_key(next) && visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        parts.push(part);
+    }
+    parts
+}
+
+/// Renders a diagram with a node per page, linking to its SVG file, and edges counting the
+/// relations between pages.
+pub fn index(pages: &[Page], options: &Options) -> Visualization {
+    let mut graph = graphviz::Graph {
+        id: "pages".to_string(),
+        ..graphviz::Graph::default()
+    };
+    if let Some(title) = &options.title {
+        graphviz::set(&mut graph.attributes, "labelloc", Value::text("t"));
+        graphviz::set(&mut graph.attributes, "label", Value::text(title));
+    }
+    let mut node = options.theme.node.clone();
+    node.shape = Some("folder".to_string());
+    let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        let mut attributes = node.attributes();
+        graphviz::set(
+            &mut attributes,
+            "label",
+            Value::Text(format!(
+                "{}\n{} components",
+                page.name,
+                page.components.len()
+            )),
+        );
+        graphviz::set(
+            &mut attributes,
+            "URL",
+            Value::Text(format!("{}.svg", page.file_name())),
+        );
+        graph.nodes.push(graphviz::Node {
+            id: format!("page_{}", i),
+            attributes,
+        });
+        let internal = page
+            .components
+            .iter()
+            .map(|c| &c.id)
+            .collect::<HashSet<_>>();
+        for stub in page
+            .stubs
+            .iter()
+            .filter(|s| internal.contains(&s.relation.from))
+        {
+            *counts.entry((i, stub.page)).or_default() += 1;
+        }
+    }
+    let edge = options.theme.edge.attributes();
+    for ((from, to), count) in counts.into_iter() {
+        let mut attributes = edge.clone();
+        graphviz::set(
+            &mut attributes,
+            "label",
+            Value::Text(format!("{} relations", count)),
+        );
+        graph.edges.push(graphviz::Edge {
+            from: format!("page_{}", from),
+            to: format!("page_{}", to),
+            attributes,
+        });
+    }
+    graph.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::fixtures;
+    use crate::architecture::partition::{index, partition, Partitioning};
+    use crate::architecture::visualization::Options;
+    use crate::graphviz::parser;
+
+    #[test]
+    fn stubs_relations_between_domains() {
+        let pages = partition(
+            &fixtures::components(),
+            &fixtures::relations(),
+            Partitioning::Domain,
+        );
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].file_name(), "business-architecture");
+        assert_eq!(pages[0].components.len(), 5);
+        assert_eq!(pages[0].stubs.len(), 2);
+        assert_eq!(pages[1].stubs.len(), 2);
+        let content = pages[0]
+            .visualization(&pages, &Options::default())
+            .graphviz_content;
+        assert!(content
+            .contains("label=\"knowledge application service\\n→ Application architecture\""));
+        assert!(content.contains("URL=\"application-architecture.svg\""));
+        assert_eq!(parser::parse(&content), Ok(()));
+
+        let content = index(&pages, &Options::default()).graphviz_content;
+        assert!(content.contains(
+            "page_1 -> page_0 [color=\"#525252\",fontname=\"Helvetica\",label=\"2 relations\"]"
+        ));
+        assert_eq!(parser::parse(&content), Ok(()));
+    }
+
+    #[test]
+    fn keeps_pages_within_size() {
+        let components = fixtures::components();
+        let relations = fixtures::relations();
+
+        assert_eq!(
+            partition(&components, &relations, Partitioning::Connected).len(),
+            1
+        );
+        let pages = partition(&components, &relations, Partitioning::Size(3));
+        assert_eq!(
+            pages.iter().map(|p| p.components.len()).collect::<Vec<_>>(),
+            [3, 3, 2]
+        );
+        let stubs = pages.iter().map(|p| p.stubs.len()).sum::<usize>();
+        let internal = pages.iter().map(|p| p.relations.len()).sum::<usize>();
+        assert_eq!(stubs / 2 + internal, relations.len());
+    }
+}
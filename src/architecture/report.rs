@@ -0,0 +1,316 @@
+//! A summary report of the architecture, to publish alongside other documentation.
+//!
+//! The report counts the components per domain and kind, lists the components added since a
+//! baseline, the lint and coverage findings and the components with the most incoming
+//! relations, and embeds diagrams. It is written as Markdown, and as HTML directly, with all
+//! text from the architecture escaped in either.
+
+use super::coverage::Coverage;
+use super::diff::{Change, Diff};
+use super::lint::Finding;
+use super::{ArchitectureDomain, Component, ComponentKind, Relation};
+use crate::table::Table;
+use crate::xml::escape;
+use std::fmt::Write;
+
+/// The number of components listed by fan-in.
+const FAN_IN: usize = 5;
+
+#[derive(Debug)]
+pub struct Report<'a> {
+    pub title: String,
+    pub components: &'a [Component],
+    pub relations: &'a [Relation],
+    pub coverage: &'a Coverage,
+    pub findings: &'a [Finding],
+    /// The changes since a baseline, if any.
+    pub diff: Option<&'a Diff>,
+    /// Diagrams to embed, as pairs of a caption and a path relative to the report.
+    pub diagrams: Vec<(String, String)>,
+}
+
+/// A part of the report, rendered as Markdown or as HTML.
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Table(Table),
+    /// A diagram with a caption and a path.
+    Image(String, String),
+}
+
+impl Report<'_> {
+    fn blocks(&self) -> Vec<Block> {
+        let mut blocks = vec![
+            Block::Heading(1, self.title.to_string()),
+            Block::Paragraph(format!(
+                "The architecture has {} components and {} relations.",
+                self.components.len(),
+                self.relations.len()
+            )),
+            Block::Heading(2, "Components".to_string()),
+            Block::Table(self.domains_table()),
+            Block::Table(self.kinds_table()),
+        ];
+
+        if let Some(diff) = self.diff {
+            blocks.push(Block::Heading(2, "Added since the baseline".to_string()));
+            let added = diff
+                .components
+                .iter()
+                .filter(|(_, change)| *change == Change::Added)
+                .map(|(c, _)| vec![c.label.to_string(), c.kind.label().to_string()])
+                .collect::<Vec<_>>();
+            blocks.push(if added.is_empty() {
+                Block::Paragraph("No components were added.".to_string())
+            } else {
+                Block::Table(table(&["Component", "Kind"], added))
+            });
+        }
+
+        blocks.push(Block::Heading(2, "Lint findings".to_string()));
+        blocks.push(if self.findings.is_empty() {
+            Block::Paragraph("No findings.".to_string())
+        } else {
+            Block::Table(self.findings_table())
+        });
+
+        blocks.push(Block::Heading(2, "Coverage".to_string()));
+        blocks.push(Block::Table(self.coverage.scores_table()));
+        if !self.coverage.missing.is_empty() {
+            blocks.push(Block::Table(self.coverage.missing_table()));
+        }
+
+        blocks.push(Block::Heading(2, "Most incoming relations".to_string()));
+        let fan_in = self.fan_in();
+        blocks.push(if fan_in.is_empty() {
+            Block::Paragraph("No relations.".to_string())
+        } else {
+            let rows = fan_in
+                .iter()
+                .map(|(c, n)| {
+                    vec![
+                        c.label.to_string(),
+                        c.kind.label().to_string(),
+                        n.to_string(),
+                    ]
+                })
+                .collect();
+            Block::Table(table(&["Component", "Kind", "Incoming relations"], rows))
+        });
+
+        if !self.diagrams.is_empty() {
+            blocks.push(Block::Heading(2, "Diagrams".to_string()));
+            for (caption, path) in self.diagrams.iter() {
+                blocks.push(Block::Image(caption.to_string(), path.to_string()));
+            }
+        }
+        blocks
+    }
+
+    /// Renders as Markdown, with all text escaped so that it shows literally.
+    pub fn to_markdown(&self) -> String {
+        let blocks = self
+            .blocks()
+            .into_iter()
+            .map(|block| match block {
+                Block::Heading(level, text) => {
+                    format!("{} {}\n", "#".repeat(level), markdown(&text))
+                }
+                Block::Paragraph(text) => format!("{}\n", markdown(&text)),
+                Block::Table(t) => Table {
+                    header: t.header.iter().map(|c| markdown(c)).collect(),
+                    rows: t
+                        .rows
+                        .iter()
+                        .map(|r| r.iter().map(|c| markdown(c)).collect())
+                        .collect(),
+                }
+                .to_markdown(),
+                Block::Image(caption, path) => {
+                    let path = path
+                        .replace(' ', "%20")
+                        .replace('(', "%28")
+                        .replace(')', "%29");
+                    format!("![{}]({})\n", markdown(&caption), path)
+                }
+            })
+            .collect::<Vec<_>>();
+        blocks.join("\n")
+    }
+
+    /// Renders as a page with the `main.css` stylesheet next to it.
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        for block in self.blocks() {
+            match block {
+                Block::Heading(level, text) => {
+                    writeln!(body, "<h{}>{}</h{}>", level, escape(&text), level).unwrap()
+                }
+                Block::Paragraph(text) => writeln!(body, "<p>{}</p>", escape(&text)).unwrap(),
+                Block::Table(t) => body.push_str(&t.to_html()),
+                Block::Image(caption, path) => writeln!(
+                    body,
+                    "<p><img src=\"{}\" alt=\"{}\"></p>",
+                    escape(&path),
+                    escape(&caption)
+                )
+                .unwrap(),
+            }
+        }
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{}</title>\n<link rel=\"stylesheet\" href=\"main.css\">\n</head>\n<body>\n\
+             {}</body>\n</html>\n",
+            escape(&self.title),
+            body
+        )
+    }
+
+    fn domains_table(&self) -> Table {
+        let rows = ArchitectureDomain::ALL
+            .iter()
+            .map(|d| {
+                let count = self
+                    .components
+                    .iter()
+                    .filter(|c| c.kind.domain() == *d)
+                    .count();
+                (d, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .map(|(d, count)| vec![d.label().to_string(), count.to_string()])
+            .collect();
+        table(&["Domain", "Components"], rows)
+    }
+
+    fn kinds_table(&self) -> Table {
+        let rows = ComponentKind::ALL
+            .iter()
+            .map(|k| (k, self.components.iter().filter(|c| c.kind == *k).count()))
+            .filter(|(_, count)| *count > 0)
+            .map(|(k, count)| {
+                vec![
+                    k.label().to_string(),
+                    k.domain().label().to_string(),
+                    count.to_string(),
+                ]
+            })
+            .collect();
+        table(&["Kind", "Domain", "Components"], rows)
+    }
+
+    fn findings_table(&self) -> Table {
+        let rows = self
+            .findings
+            .iter()
+            .map(|f| {
+                let component = self
+                    .components
+                    .iter()
+                    .find(|c| c.id.value == f.component)
+                    .map_or_else(|| f.component.to_string(), |c| c.label.to_string());
+                vec![
+                    f.severity.to_string(),
+                    f.rule.name().to_string(),
+                    component,
+                    f.message.to_string(),
+                ]
+            })
+            .collect();
+        table(&["Severity", "Rule", "Component", "Message"], rows)
+    }
+
+    /// The components with the most incoming relations, by descending count and then by label.
+    fn fan_in(&self) -> Vec<(&Component, usize)> {
+        let mut counts = self
+            .components
+            .iter()
+            .map(|c| (c, self.relations.iter().filter(|r| r.to == c.id).count()))
+            .filter(|(_, n)| *n > 0)
+            .collect::<Vec<_>>();
+        counts.sort_by(|(a, m), (b, n)| n.cmp(m).then_with(|| a.label.cmp(&b.label)));
+        counts.truncate(FAN_IN);
+        counts
+    }
+}
+
+/// Escapes the characters that Markdown would take as markup or HTML.
+fn markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if "\\`*_[]<>&#".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> Table {
+    Table {
+        header: header.iter().map(|h| h.to_string()).collect(),
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::coverage::Coverage;
+    use crate::architecture::diff::diff;
+    use crate::architecture::fixtures;
+    use crate::architecture::lint::{Finding, Rule, Severity};
+    use crate::architecture::report::Report;
+
+    #[test]
+    fn summarizes_architecture() {
+        let mut components = fixtures::components();
+        components[1].label = "<img src=x onerror=alert(1)> *knowledge* service".to_string();
+        let relations = fixtures::relations();
+        let diff = diff(&components[1..], &[], &components, &relations);
+        let coverage = Coverage {
+            missing: vec![],
+            scores: vec![],
+        };
+        let findings = vec![Finding {
+            rule: Rule::MissingComment,
+            severity: Severity::Warning,
+            component: fixtures::id("knowledge-service").value,
+            graph: fixtures::GRAPH.to_string(),
+            message: "no comment".to_string(),
+        }];
+        let report = Report {
+            title: "Architecture".to_string(),
+            components: &components,
+            relations: &relations,
+            coverage: &coverage,
+            findings: &findings,
+            diff: Some(&diff),
+            diagrams: vec![("Overview [all]".to_string(), "example.svg".to_string())],
+        };
+
+        let markdown = report.to_markdown();
+
+        assert!(markdown.contains("The architecture has 8 components and 8 relations."));
+        assert!(markdown.contains("| Business architecture | 5 |\n"));
+        assert!(markdown.contains("| Function | Business architecture | 2 |\n"));
+        assert!(markdown.contains(
+            "## Added since the baseline\n\n\
+             | Component | Kind |\n\
+             | --- | --- |\n\
+             | knowledge management | Function |\n"
+        ));
+        assert!(markdown.contains(
+            "| warning | missing-comment | \\<img src=x onerror=alert(1)\\> \\*knowledge\\* service \
+             | no comment |"
+        ));
+        assert!(markdown.contains(
+            "| --- | --- | --- |\n\
+             | knowledge management | Function | 2 |\n"
+        ));
+        assert!(markdown.ends_with("![Overview \\[all\\]](example.svg)\n"));
+        let html = report.to_html();
+        assert!(html.contains("<td>knowledge management</td>"));
+        assert!(html.contains("<td>&lt;img src=x onerror=alert(1)&gt; *knowledge* service</td>"));
+        assert!(html.contains("<img src=\"example.svg\" alt=\"Overview [all]\">"));
+    }
+}
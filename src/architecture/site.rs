@@ -0,0 +1,384 @@
+//! A static documentation site with a page for each component, and index pages by kind and
+//! by domain.
+//!
+//! Component pages link to each other through their relations, show a diagram of the direct
+//! neighbourhood of the component, and list the Markdown documents that link to its IRI. The
+//! documents are pages too, with the labels of the components they link to. All pages use the
+//! `main.css` stylesheet at the root of the site. Pages are named after the slugs of components
+//! and the paths of documents, numbered where these collide.
+
+use super::focus::{Direction, Focus};
+use super::visualization::{Options, Visualization};
+use super::{distinct, file_names, slug, ArchitectureDomain, Component, ComponentKind, Relation};
+use crate::document::{Document, MarkdownDocument};
+use crate::knowledge::Resource;
+use crate::xml::escape;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// A file of the site, with its path relative to the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub struct Site {
+    pub pages: Vec<Page>,
+    /// The diagrams that component pages show, by path relative to the root and without the
+    /// extension, which should be `.svg`.
+    pub diagrams: Vec<(String, Visualization)>,
+}
+
+/// A Markdown document by path.
+struct Source<'a> {
+    title: String,
+    file: String,
+    links: Vec<Resource>,
+    content: &'a str,
+}
+
+/// Generates the site, with documents as pairs of a path and Markdown content.
+pub fn site(
+    components: &[Component],
+    relations: &[Relation],
+    documents: &[(String, String)],
+    options: &Options,
+) -> Site {
+    let by_id = components
+        .iter()
+        .map(|c| (&c.id, c))
+        .collect::<HashMap<_, _>>();
+    let names = file_names(components);
+    let mut taken = HashSet::new();
+    let sources = documents
+        .iter()
+        .map(|(path, content)| Source {
+            title: content
+                .lines()
+                .find_map(|l| l.strip_prefix("# "))
+                .unwrap_or(path)
+                .trim()
+                .to_string(),
+            file: format!(
+                "{}.html",
+                distinct(&slug(path.trim_end_matches(".md")), "document", &mut taken)
+            ),
+            links: MarkdownDocument(content)
+                .get_outbound_links()
+                .into_iter()
+                .collect(),
+            content,
+        })
+        .collect::<Vec<_>>();
+    let link = |c: &Component| {
+        format!(
+            "<a href=\"{}.html\">{}</a>",
+            escape(&names[&c.id]),
+            escape(&c.label)
+        )
+    };
+
+    let mut pages = vec![];
+    let mut diagrams = vec![];
+    let mut diagram_options = options.clone();
+    diagram_options.link = Some("$SLUG.html".to_string());
+    diagram_options.file_names = names.clone();
+    for c in components.iter() {
+        let name = &names[&c.id];
+        let mut body = format!("<h1>{}</h1>\n", escape(&c.label));
+        writeln!(
+            body,
+            "<p><a href=\"../kinds/{}.html\">{}</a> in <a href=\"../domains/{}.html\">{}</a></p>",
+            c.kind.name(),
+            escape(c.kind.label()),
+            c.kind.domain().name(),
+            escape(c.kind.domain().label())
+        )
+        .unwrap();
+        if let Some(d) = &c.description {
+            writeln!(body, "<p>{}</p>", escape(d)).unwrap();
+        }
+        let outgoing = relations
+            .iter()
+            .filter(|r| r.from == c.id)
+            .filter_map(|r| Some((r, by_id.get(&r.to)?)))
+            .map(|(r, to)| format!("{} {}", escape(r.kind.label()), link(to)))
+            .collect::<Vec<_>>();
+        let incoming = relations
+            .iter()
+            .filter(|r| r.to == c.id)
+            .filter_map(|r| Some((r, by_id.get(&r.from)?)))
+            .map(|(r, from)| format!("{} {}", link(from), escape(r.kind.label())))
+            .collect::<Vec<_>>();
+        body.push_str("<h2>Relations</h2>\n");
+        if outgoing.is_empty() && incoming.is_empty() {
+            body.push_str("<p>No relations.</p>\n");
+        }
+        list(&mut body, "Outgoing", &outgoing);
+        list(&mut body, "Incoming", &incoming);
+
+        let focus = Focus {
+            component: c.id.clone(),
+            depth: 1,
+            direction: Direction::Both,
+        };
+        diagrams.push((
+            format!("components/{}", name),
+            focus.visualization(components, relations, &diagram_options),
+        ));
+        writeln!(
+            body,
+            "<h2>Diagram</h2>\n<object data=\"{}.svg\" type=\"image/svg+xml\">{}</object>",
+            escape(name),
+            escape(&c.label)
+        )
+        .unwrap();
+
+        body.push_str("<h2>Mentioned in documents</h2>\n");
+        let resource = Resource::from(c.id.value.as_str());
+        let mentions = sources
+            .iter()
+            .filter(|s| s.links.contains(&resource))
+            .map(|s| {
+                format!(
+                    "<a href=\"../documents/{}\">{}</a>",
+                    s.file,
+                    escape(&s.title)
+                )
+            })
+            .collect::<Vec<_>>();
+        if mentions.is_empty() {
+            body.push_str("<p>Not mentioned in any document.</p>\n");
+        } else {
+            list(&mut body, "", &mentions);
+        }
+        writeln!(body, "<p><small>{}</small></p>", escape(&c.id.value)).unwrap();
+        pages.push(Page {
+            path: format!("components/{}.html", name),
+            content: page(&c.label, "../", &body),
+        });
+    }
+
+    let in_components = |c: &Component| link(c).replace("href=\"", "href=\"../components/");
+    let mut index = "<h1>Architecture</h1>\n".to_string();
+    let mut kinds = vec![];
+    for kind in ComponentKind::ALL.iter() {
+        let members = components
+            .iter()
+            .filter(|c| c.kind == *kind)
+            .map(in_components)
+            .collect::<Vec<_>>();
+        if members.is_empty() {
+            continue;
+        }
+        kinds.push(format!(
+            "<a href=\"kinds/{}.html\">{}</a> ({})",
+            kind.name(),
+            escape(kind.label()),
+            members.len()
+        ));
+        let mut body = format!("<h1>{}</h1>\n", escape(kind.label()));
+        list(&mut body, "", &members);
+        pages.push(Page {
+            path: format!("kinds/{}.html", kind.name()),
+            content: page(kind.label(), "../", &body),
+        });
+    }
+    let mut domains = vec![];
+    for domain in ArchitectureDomain::ALL.iter() {
+        let mut body = format!("<h1>{}</h1>\n", escape(domain.label()));
+        let mut count = 0;
+        for kind in ComponentKind::ALL.iter().filter(|k| k.domain() == *domain) {
+            let members = components
+                .iter()
+                .filter(|c| c.kind == *kind)
+                .map(in_components)
+                .collect::<Vec<_>>();
+            count += members.len();
+            list(&mut body, kind.label(), &members);
+        }
+        if count == 0 {
+            continue;
+        }
+        domains.push(format!(
+            "<a href=\"domains/{}.html\">{}</a> ({})",
+            domain.name(),
+            escape(domain.label()),
+            count
+        ));
+        pages.push(Page {
+            path: format!("domains/{}.html", domain.name()),
+            content: page(domain.label(), "../", &body),
+        });
+    }
+    list(&mut index, "By domain", &domains);
+    list(&mut index, "By kind", &kinds);
+    let documents = sources
+        .iter()
+        .map(|s| format!("<a href=\"documents/{}\">{}</a>", s.file, escape(&s.title)))
+        .collect::<Vec<_>>();
+    list(&mut index, "Documents", &documents);
+    pages.push(Page {
+        path: "index.html".to_string(),
+        content: page("Architecture", "", &index),
+    });
+
+    for s in sources.iter() {
+        let labels = s
+            .links
+            .iter()
+            .filter_map(|l| {
+                let c = components
+                    .iter()
+                    .find(|c| Resource::from(c.id.value.as_str()) == *l)?;
+                Some((l.clone(), c.label.to_string()))
+            })
+            .collect();
+        let mut body = MarkdownDocument(s.content).render_with_replaced_labels(labels);
+        for c in components.iter() {
+            body = body.replace(
+                &format!("href=\"{}\"", escape(&c.id.value)),
+                &format!("href=\"../components/{}.html\"", escape(&names[&c.id])),
+            );
+        }
+        pages.push(Page {
+            path: format!("documents/{}", s.file),
+            content: page(&s.title, "../", &body),
+        });
+    }
+
+    Site { pages, diagrams }
+}
+
+/// Writes a list with a heading, unless it has no items.
+fn list(body: &mut String, heading: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    if !heading.is_empty() {
+        writeln!(body, "<h3>{}</h3>", escape(heading)).unwrap();
+    }
+    body.push_str("<ul>\n");
+    for item in items.iter() {
+        writeln!(body, "<li>{}</li>", item).unwrap();
+    }
+    body.push_str("</ul>\n");
+}
+
+/// Writes a page with the stylesheet at the root, with a link to the index.
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<link rel=\"stylesheet\" href=\"{}main.css\">\n</head>\n<body>\n\
+         <nav><a href=\"{}index.html\">Architecture</a></nav>\n{}</body>\n</html>\n",
+        escape(title),
+        root,
+        root,
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::fixtures;
+    use crate::architecture::site::site;
+    use crate::architecture::visualization::Options;
+    use crate::architecture::{Component, ComponentId, ComponentKind, Relation, RelationKind};
+
+    #[test]
+    fn links_components_kinds_domains_and_documents() {
+        let documents = vec![(
+            "test.md".to_string(),
+            "# Test\n\nWriting about [modelling].\n\n\
+             [modelling]: urn:uuid:907d6137-14d5-4ef8-93bf-e967bc558fee#architecture-modelling"
+                .to_string(),
+        )];
+
+        let site = site(
+            &fixtures::components(),
+            &fixtures::relations(),
+            &documents,
+            &Options::default(),
+        );
+
+        let page = |path: &str| {
+            &site
+                .pages
+                .iter()
+                .find(|p| p.path == path)
+                .unwrap_or_else(|| panic!("no page {}", path))
+                .content
+        };
+        let modelling = page("components/architecture-modelling.html");
+        assert!(modelling.contains("<link rel=\"stylesheet\" href=\"../main.css\">"));
+        assert!(modelling.contains(
+            "<li><a href=\"architecture-management.html\">architecture management</a> \
+             supports or is realized by</li>"
+        ));
+        assert!(modelling.contains("<object data=\"architecture-modelling.svg\""));
+        assert!(modelling.contains("<li><a href=\"../documents/test.html\">Test</a></li>"));
+        assert!(page("components/knowledge-service.html")
+            .contains("<p>Not mentioned in any document.</p>"));
+        assert!(page("kinds/Process.html").contains(
+            "<li><a href=\"../components/architecture-modelling.html\">architecture modelling</a></li>"
+        ));
+        assert!(page("domains/business.html").contains("<h3>Business service</h3>"));
+        assert!(page("index.html").contains("<a href=\"domains/application.html\">"));
+        assert!(page("documents/test.html").contains(
+            "<a href=\"../components/architecture-modelling.html\">architecture modelling</a>"
+        ));
+        assert_eq!(site.diagrams.len(), fixtures::components().len());
+        assert_eq!(site.diagrams[0].0, "components/knowledge-management");
+    }
+
+    #[test]
+    fn numbers_colliding_names() {
+        let component = |iri: &str| Component {
+            id: ComponentId {
+                value: iri.to_string(),
+            },
+            label: iri.to_string(),
+            description: None,
+            kind: ComponentKind::BusinessService,
+            graph: fixtures::GRAPH.to_string(),
+        };
+        let components = vec![
+            component("http://example.com/a#portal"),
+            component("http://example.com/b#portal"),
+        ];
+        let relations = vec![Relation {
+            from: components[0].id.clone(),
+            to: components[1].id.clone(),
+            kind: RelationKind::InformationSystemServiceRealizesBusinessService,
+        }];
+        let documents = vec![
+            ("a/b.md".to_string(), "# A/B".to_string()),
+            ("a-b.md".to_string(), "# A-B".to_string()),
+        ];
+
+        let site = site(&components, &relations, &documents, &Options::default());
+
+        let paths = site
+            .pages
+            .iter()
+            .map(|p| p.path.as_str())
+            .collect::<Vec<_>>();
+        assert!(paths.contains(&"components/portal.html"));
+        assert!(paths.contains(&"components/portal-2.html"));
+        assert!(paths.contains(&"documents/a-b.html"));
+        assert!(paths.contains(&"documents/a-b-2.html"));
+        assert!(site.pages[0]
+            .content
+            .contains("<a href=\"portal-2.html\">http://example.com/b#portal</a>"));
+        assert!(site.pages[1]
+            .content
+            .contains("<object data=\"portal-2.svg\""));
+        assert_eq!(site.diagrams[1].0, "components/portal-2");
+        assert!(site.diagrams[0]
+            .1
+            .graphviz_content
+            .contains("URL=\"portal-2.html\""));
+    }
+}
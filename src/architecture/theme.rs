@@ -0,0 +1,369 @@
+//! Styles for diagrams, by component kind, architecture domain and relation kind.
+//!
+//! Besides the built-in themes, a theme can be loaded from a JSON file such as:
+//!
+//! ```json
+//! {
+//!   "name": "ours",
+//!   "cluster": { "style": "rounded", "color": "grey" },
+//!   "node": { "shape": "box", "style": "rounded,filled", "fontName": "Helvetica" },
+//!   "domains": { "business": { "fillColor": "#fff2cc" } },
+//!   "kinds": { "Process": { "shape": "cds" } },
+//!   "relations": { "informationSystemServiceRealizesBusinessService": { "style": "dashed" } }
+//! }
+//! ```
+//!
+//! Style values are Graphviz attribute values.
+
+use super::{ArchitectureDomain, ComponentKind, RelationKind};
+use crate::graphviz::{Attributes, Value};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct NodeStyle {
+    pub shape: Option<String>,
+    pub style: Option<String>,
+    pub fill_color: Option<String>,
+    pub color: Option<String>,
+    pub font_name: Option<String>,
+    pub font_color: Option<String>,
+    pub pen_width: Option<String>,
+}
+
+impl NodeStyle {
+    /// Overrides this style with each attribute that `other` sets.
+    pub fn merge(&mut self, other: &NodeStyle) {
+        let NodeStyle {
+            shape,
+            style,
+            fill_color,
+            color,
+            font_name,
+            font_color,
+            pen_width,
+        } = other.clone();
+        self.shape = shape.or_else(|| self.shape.take());
+        self.style = style.or_else(|| self.style.take());
+        self.fill_color = fill_color.or_else(|| self.fill_color.take());
+        self.color = color.or_else(|| self.color.take());
+        self.font_name = font_name.or_else(|| self.font_name.take());
+        self.font_color = font_color.or_else(|| self.font_color.take());
+        self.pen_width = pen_width.or_else(|| self.pen_width.take());
+    }
+
+    pub fn attributes(&self) -> Attributes {
+        attributes(&[
+            ("shape", &self.shape),
+            ("style", &self.style),
+            ("fillcolor", &self.fill_color),
+            ("color", &self.color),
+            ("fontname", &self.font_name),
+            ("fontcolor", &self.font_color),
+            ("penwidth", &self.pen_width),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct EdgeStyle {
+    /// Such as `solid`, `dashed` or `dotted`.
+    pub style: Option<String>,
+    pub color: Option<String>,
+    pub arrow_head: Option<String>,
+    pub font_name: Option<String>,
+    pub font_color: Option<String>,
+    pub pen_width: Option<String>,
+}
+
+impl EdgeStyle {
+    /// Overrides this style with each attribute that `other` sets.
+    pub fn merge(&mut self, other: &EdgeStyle) {
+        let EdgeStyle {
+            style,
+            color,
+            arrow_head,
+            font_name,
+            font_color,
+            pen_width,
+        } = other.clone();
+        self.style = style.or_else(|| self.style.take());
+        self.color = color.or_else(|| self.color.take());
+        self.arrow_head = arrow_head.or_else(|| self.arrow_head.take());
+        self.font_name = font_name.or_else(|| self.font_name.take());
+        self.font_color = font_color.or_else(|| self.font_color.take());
+        self.pen_width = pen_width.or_else(|| self.pen_width.take());
+    }
+
+    pub fn attributes(&self) -> Attributes {
+        attributes(&[
+            ("style", &self.style),
+            ("color", &self.color),
+            ("arrowhead", &self.arrow_head),
+            ("fontname", &self.font_name),
+            ("fontcolor", &self.font_color),
+            ("penwidth", &self.pen_width),
+        ])
+    }
+}
+
+fn attributes(values: &[(&str, &Option<String>)]) -> Attributes {
+    values
+        .iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| (name.to_string(), Value::text(v))))
+        .collect()
+}
+
+/// Node styles by domain and by kind on top of a base style, and edge styles by relation kind on
+/// top of a base style. Kind styles take precedence over domain styles.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub name: String,
+    /// The style of clusters when grouping components, of which the shape is ignored.
+    pub cluster: NodeStyle,
+    /// The style of highlighted components, on top of their own style.
+    pub highlight: NodeStyle,
+    pub node: NodeStyle,
+    pub domains: HashMap<ArchitectureDomain, NodeStyle>,
+    pub kinds: HashMap<ComponentKind, NodeStyle>,
+    pub edge: EdgeStyle,
+    pub relations: HashMap<RelationKind, EdgeStyle>,
+}
+
+fn node(shape: &str, fill_color: &str) -> NodeStyle {
+    NodeStyle {
+        shape: Some(shape.to_string()),
+        fill_color: Some(fill_color.to_string()),
+        ..NodeStyle::default()
+    }
+}
+
+fn edge(style: &str, arrow_head: &str) -> EdgeStyle {
+    EdgeStyle {
+        style: Some(style.to_string()),
+        arrow_head: Some(arrow_head.to_string()),
+        ..EdgeStyle::default()
+    }
+}
+
+impl Theme {
+    pub const BUILT_IN: [&'static str; 2] = ["togaf", "archimate"];
+
+    /// A distinct shape and colour per component kind, in warm shades for business and cool
+    /// shades for applications, with realization dashed.
+    pub fn togaf() -> Theme {
+        let kinds = vec![
+            (ComponentKind::BusinessService, node("box", "#fdd49e")),
+            (ComponentKind::Function, node("box", "#fee8c8")),
+            (ComponentKind::Process, node("cds", "#fdbb84")),
+            (
+                ComponentKind::InformationSystemService,
+                node("box", "#c6dbef"),
+            ),
+            (
+                ComponentKind::LogicalApplicationComponent,
+                node("component", "#9ecae1"),
+            ),
+            (
+                ComponentKind::PhysicalApplicationComponent,
+                node("component", "#6baed6"),
+            ),
+            (ComponentKind::DataEntity, node("cylinder", "#c7e9c0")),
+            (ComponentKind::PlatformService, node("box3d", "#d9d9d9")),
+        ];
+        let relations = vec![
+            (
+                RelationKind::InformationSystemServiceRealizesBusinessService,
+                edge("dashed", "empty"),
+            ),
+            (
+                RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+                edge("dashed", "normal"),
+            ),
+            (
+                RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+                edge("dashed", "empty"),
+            ),
+            (
+                RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent,
+                edge("solid", "onormal"),
+            ),
+        ];
+        Theme {
+            name: "togaf".to_string(),
+            highlight: NodeStyle {
+                color: Some("#d7301f".to_string()),
+                pen_width: Some("3".to_string()),
+                ..NodeStyle::default()
+            },
+            cluster: NodeStyle {
+                style: Some("rounded".to_string()),
+                color: Some("#bdbdbd".to_string()),
+                font_name: Some("Helvetica".to_string()),
+                ..NodeStyle::default()
+            },
+            node: NodeStyle {
+                style: Some("rounded,filled".to_string()),
+                color: Some("#525252".to_string()),
+                font_name: Some("Helvetica".to_string()),
+                ..NodeStyle::default()
+            },
+            domains: HashMap::new(),
+            kinds: kinds.into_iter().collect(),
+            edge: EdgeStyle {
+                color: Some("#525252".to_string()),
+                font_name: Some("Helvetica".to_string()),
+                ..EdgeStyle::default()
+            },
+            relations: relations.into_iter().collect(),
+        }
+    }
+
+    /// The layer colours of the ArchiMate specification, with data in the application layer as
+    /// passive structure.
+    pub fn archimate() -> Theme {
+        let domains = vec![
+            (ArchitectureDomain::Business, node("box", "#ffffb5")),
+            (ArchitectureDomain::Data, node("note", "#b5ffff")),
+            (ArchitectureDomain::Application, node("box", "#b5ffff")),
+            (ArchitectureDomain::Technology, node("box3d", "#c9e7b7")),
+        ];
+        let kinds = vec![
+            (
+                ComponentKind::LogicalApplicationComponent,
+                node("component", "#b5ffff"),
+            ),
+            (
+                ComponentKind::PhysicalApplicationComponent,
+                node("component", "#b5ffff"),
+            ),
+        ];
+        let realization = edge("dashed", "empty");
+        let relations = vec![
+            (
+                RelationKind::InformationSystemServiceRealizesBusinessService,
+                realization.clone(),
+            ),
+            (
+                RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+                realization.clone(),
+            ),
+            (
+                RelationKind::FunctionIsRealizedByAndOrSupportsProcess,
+                realization,
+            ),
+            (
+                RelationKind::ProcessDecomposesAndOrOrchestratesFunction,
+                edge("solid", "odiamond"),
+            ),
+        ];
+        Theme {
+            name: "archimate".to_string(),
+            highlight: NodeStyle {
+                color: Some("red".to_string()),
+                pen_width: Some("3".to_string()),
+                ..NodeStyle::default()
+            },
+            cluster: NodeStyle {
+                style: Some("dashed".to_string()),
+                color: Some("black".to_string()),
+                font_name: Some("Arial".to_string()),
+                ..NodeStyle::default()
+            },
+            node: NodeStyle {
+                style: Some("rounded,filled".to_string()),
+                color: Some("black".to_string()),
+                font_name: Some("Arial".to_string()),
+                ..NodeStyle::default()
+            },
+            domains: domains.into_iter().collect(),
+            kinds: kinds.into_iter().collect(),
+            edge: EdgeStyle {
+                color: Some("black".to_string()),
+                font_name: Some("Arial".to_string()),
+                ..EdgeStyle::default()
+            },
+            relations: relations.into_iter().collect(),
+        }
+    }
+
+    pub fn built_in(name: &str) -> Option<Theme> {
+        match name {
+            "togaf" => Some(Theme::togaf()),
+            "archimate" => Some(Theme::archimate()),
+            _ => None,
+        }
+    }
+
+    pub fn from_json(content: &str) -> serde_json::Result<Theme> {
+        serde_json::from_str(content)
+    }
+
+    pub fn node_style(&self, kind: ComponentKind) -> NodeStyle {
+        let mut style = self.node.clone();
+        if let Some(s) = self.domains.get(&kind.domain()) {
+            style.merge(s);
+        }
+        if let Some(s) = self.kinds.get(&kind) {
+            style.merge(s);
+        }
+        style
+    }
+
+    pub fn edge_style(&self, kind: RelationKind) -> EdgeStyle {
+        let mut style = self.edge.clone();
+        if let Some(s) = self.relations.get(&kind) {
+            style.merge(s);
+        }
+        style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::theme::Theme;
+    use crate::architecture::{ComponentKind, RelationKind};
+
+    #[test]
+    fn applies_kind_over_domain_over_base() {
+        let theme = Theme::from_json(
+            r##"{
+                "name": "ours",
+                "node": { "shape": "box", "fontName": "Helvetica" },
+                "domains": { "business": { "shape": "ellipse", "fillColor": "#fff2cc" } },
+                "kinds": { "Process": { "shape": "cds" } },
+                "relations": { "informationSystemServiceRealizesBusinessService": { "style": "dashed" } }
+            }"##,
+        )
+        .unwrap();
+
+        let process = theme.node_style(ComponentKind::Process);
+        assert_eq!(process.shape.as_deref(), Some("cds"));
+        assert_eq!(process.fill_color.as_deref(), Some("#fff2cc"));
+        assert_eq!(process.font_name.as_deref(), Some("Helvetica"));
+        let service = theme.node_style(ComponentKind::BusinessService);
+        assert_eq!(service.shape.as_deref(), Some("ellipse"));
+        assert_eq!(
+            theme
+                .edge_style(RelationKind::InformationSystemServiceRealizesBusinessService)
+                .style
+                .as_deref(),
+            Some("dashed")
+        );
+        assert!(Theme::from_json(r#"{ "node": { "colour": "red" } }"#).is_err());
+    }
+
+    #[test]
+    fn tells_built_in_kinds_apart() {
+        for name in Theme::BUILT_IN.iter() {
+            let theme = Theme::built_in(name).unwrap();
+            assert_ne!(
+                theme.node_style(ComponentKind::BusinessService),
+                theme.node_style(ComponentKind::LogicalApplicationComponent)
+            );
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Traceability from functions, through business services and information system services, to
+//! the application components implementing them.
+
+use super::{Component, ComponentId, ComponentKind, Relation, RelationKind};
+use crate::table::Table;
+use std::collections::HashMap;
+
+/// A link missing from a chain.
+#[derive(Debug, PartialEq)]
+pub enum Gap {
+    Function,
+    InformationSystemService,
+    ApplicationComponent,
+}
+
+impl Gap {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Gap::Function => "no function",
+            Gap::InformationSystemService => "no realizing information system service",
+            Gap::ApplicationComponent => "no implementing application component",
+        }
+    }
+}
+
+/// One path from a function to an application component, through a business service.
+#[derive(Debug)]
+pub struct Chain<'a> {
+    pub function: Option<&'a Component>,
+    pub business_service: &'a Component,
+    pub information_system_service: Option<&'a Component>,
+    pub logical_application_component: Option<&'a Component>,
+    pub physical_application_component: Option<&'a Component>,
+}
+
+impl Chain<'_> {
+    pub fn gaps(&self) -> Vec<Gap> {
+        let mut gaps = vec![];
+        if self.function.is_none() {
+            gaps.push(Gap::Function);
+        }
+        if self.information_system_service.is_none() {
+            gaps.push(Gap::InformationSystemService);
+        }
+        if self.logical_application_component.is_none()
+            && self.physical_application_component.is_none()
+        {
+            gaps.push(Gap::ApplicationComponent);
+        }
+        gaps
+    }
+
+    pub fn is_broken(&self) -> bool {
+        !self.gaps().is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct Traceability<'a> {
+    pub chains: Vec<Chain<'a>>,
+}
+
+impl Traceability<'_> {
+    pub fn broken(&self) -> impl Iterator<Item = &Chain<'_>> {
+        self.chains.iter().filter(|c| c.is_broken())
+    }
+
+    pub fn table(&self) -> Table {
+        fn cell(c: Option<&Component>) -> String {
+            c.map(|c| c.label.to_string()).unwrap_or_default()
+        }
+
+        Table {
+            header: vec![
+                "Function".to_string(),
+                "Business service".to_string(),
+                "Information system service".to_string(),
+                "Logical application component".to_string(),
+                "Physical application component".to_string(),
+                "Gaps".to_string(),
+            ],
+            rows: self
+                .chains
+                .iter()
+                .map(|c| {
+                    vec![
+                        cell(c.function),
+                        c.business_service.label.to_string(),
+                        cell(c.information_system_service),
+                        cell(c.logical_application_component),
+                        cell(c.physical_application_component),
+                        c.gaps()
+                            .iter()
+                            .map(|g| g.describe())
+                            .collect::<Vec<_>>()
+                            .join("; "),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Lists every chain through every business service. A business service without any function,
+/// information system service or application component still yields a chain, which is broken.
+pub fn traceability<'a>(
+    components: &'a [Component],
+    relations: &'a [Relation],
+) -> Traceability<'a> {
+    let by_id = components
+        .iter()
+        .map(|c| (&c.id, c))
+        .collect::<HashMap<&ComponentId, &Component>>();
+
+    // Components related to `id`, following `kind` forward or backward, of the expected kind.
+    let related = |id: &ComponentId, kind: RelationKind, forward: bool, expected: ComponentKind| {
+        let mut found = relations
+            .iter()
+            .filter(|r| r.kind == kind)
+            .filter_map(|r| match forward {
+                true if &r.from == id => by_id.get(&r.to),
+                false if &r.to == id => by_id.get(&r.from),
+                _ => None,
+            })
+            .copied()
+            .filter(|c| c.kind == expected)
+            .collect::<Vec<&Component>>();
+        found.sort_by(|a, b| (&a.label, &a.id).cmp(&(&b.label, &b.id)));
+        found.dedup_by(|a, b| a.id == b.id);
+        found
+    };
+
+    let mut services = components
+        .iter()
+        .filter(|c| c.kind == ComponentKind::BusinessService)
+        .collect::<Vec<_>>();
+    services.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut chains = vec![];
+    for service in services {
+        let functions = related(
+            &service.id,
+            RelationKind::BusinessServiceProvidesGovernedInterfaceToAccessFunction,
+            true,
+            ComponentKind::Function,
+        );
+        let information_system_services = related(
+            &service.id,
+            RelationKind::InformationSystemServiceRealizesBusinessService,
+            false,
+            ComponentKind::InformationSystemService,
+        );
+        for function in optional(functions) {
+            for information_system_service in optional(information_system_services.clone()) {
+                let logical_components = match information_system_service {
+                    None => vec![],
+                    Some(s) => {
+                        let mut found = related(
+                            &s.id,
+                            RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+                            false,
+                            ComponentKind::LogicalApplicationComponent,
+                        );
+                        found.extend(related(
+                            &s.id,
+                            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+                            true,
+                            ComponentKind::LogicalApplicationComponent,
+                        ));
+                        found.sort_by(|a, b| (&a.label, &a.id).cmp(&(&b.label, &b.id)));
+                        found.dedup_by(|a, b| a.id == b.id);
+                        found
+                    }
+                };
+                for logical_application_component in optional(logical_components) {
+                    let physical_components = match logical_application_component {
+                        None => vec![],
+                        Some(c) => related(
+                            &c.id,
+                            RelationKind::PhysicalApplicationComponentExtendsLogicalApplicationComponent,
+                            false,
+                            ComponentKind::PhysicalApplicationComponent,
+                        ),
+                    };
+                    for physical_application_component in optional(physical_components) {
+                        chains.push(Chain {
+                            function,
+                            business_service: service,
+                            information_system_service,
+                            logical_application_component,
+                            physical_application_component,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Traceability { chains }
+}
+
+/// Yields each element, or a single `None` when there are none.
+fn optional<T>(elements: Vec<T>) -> Vec<Option<T>> {
+    if elements.is_empty() {
+        vec![None]
+    } else {
+        elements.into_iter().map(Some).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::fixtures;
+    use crate::architecture::traceability::{traceability, Gap};
+    use crate::architecture::{ComponentKind, RelationKind};
+
+    #[test]
+    fn follows_both_directions_to_application_components() {
+        let components = fixtures::components();
+        let relations = fixtures::relations();
+        let traceability = traceability(&components, &relations);
+
+        assert_eq!(traceability.chains.len(), 2);
+        assert_eq!(traceability.broken().count(), 0);
+        for chain in traceability.chains.iter() {
+            assert_eq!(
+                chain
+                    .logical_application_component
+                    .map(|c| c.label.as_str()),
+                Some("knowledge application service component")
+            );
+        }
+    }
+
+    #[test]
+    fn lists_each_application_component_once() {
+        let mut components = fixtures::components();
+        components.push(fixtures::component(
+            "other-component",
+            "knowledge application service component",
+            ComponentKind::LogicalApplicationComponent,
+        ));
+        let mut relations = fixtures::relations();
+        relations.push(fixtures::relation(
+            "other-component",
+            RelationKind::LogicalApplicationComponentImplementsInformationSystemService,
+            "knowledge-application-service",
+        ));
+        relations.push(fixtures::relation(
+            "knowledge-application-service",
+            RelationKind::InformationSystemServiceIsRealizedThroughLogicalApplicationComponent,
+            "knowledge-application-service-component",
+        ));
+        let traceability = traceability(&components, &relations);
+
+        let chains = traceability
+            .chains
+            .iter()
+            .filter(|c| c.business_service.label == "knowledge service")
+            .count();
+        assert_eq!(chains, 2);
+    }
+
+    #[test]
+    fn flags_business_service_without_information_system_service() {
+        let components = fixtures::components();
+        let relations = fixtures::relations()
+            .into_iter()
+            .filter(|r| r.to != fixtures::id("knowledge-service"))
+            .collect::<Vec<_>>();
+        let traceability = traceability(&components, &relations);
+
+        let broken = traceability.broken().collect::<Vec<_>>();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].business_service.label, "knowledge service");
+        assert_eq!(
+            broken[0].gaps(),
+            vec![Gap::InformationSystemService, Gap::ApplicationComponent]
+        );
+        assert_eq!(
+            traceability.table().rows[1][5],
+            "no realizing information system service; no implementing application component"
+        );
+    }
+}
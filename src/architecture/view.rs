@@ -0,0 +1,256 @@
+//! Named views on the architecture, each selecting the components and relations that concern its
+//! stakeholders.
+//!
+//! Views are defined in a JSON file, as an array of objects with the fields of [`View`], or as
+//! RDF in the dataset:
+//!
+//! ```turtle
+//! @prefix view: <https://github.com/sander/architecture-tooling/view#> .
+//! @prefix togaf: <http://www.semanticweb.org/ontologies/2020/4/OntologyTOGAFContentMetamodel.owl#> .
+//! @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+//!
+//! <#business-services>
+//!   a view:View ;
+//!   rdfs:label "business services" ;
+//!   view:kind togaf:BusinessService, togaf:Function ;
+//!   view:relation togaf:businessServiceProvidesGovernedInterfaceToAccessFunction ;
+//!   view:stakeholder "business owners" ;
+//!   view:concern "which functions are accessible as services" .
+//! ```
+//!
+//! Besides `view:kind`, `view:relation`, `view:stakeholder` and `view:concern`, views may use
+//! `view:domain` with a domain name literal, and `view:graph` and `view:component` with IRIs.
+
+use super::visualization::{visualize, Options, Visualization};
+use super::{
+    distinct, slug, ArchitectureDomain, Component, ComponentKind, Relation, RelationKind, Statement,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+
+pub const NAMESPACE: &str = "https://github.com/sander/architecture-tooling/view#";
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+
+/// A selection of components and relations. Each non-empty criterion restricts the selection.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct View {
+    pub name: String,
+    pub kinds: Vec<ComponentKind>,
+    pub domains: Vec<ArchitectureDomain>,
+    /// IRIs of the named graphs defining the components.
+    pub graphs: Vec<String>,
+    /// IRIs of the components.
+    pub components: Vec<String>,
+    pub relations: Vec<RelationKind>,
+    pub stakeholders: Vec<String>,
+    pub concerns: Vec<String>,
+}
+
+impl View {
+    pub fn from_json(content: &str) -> serde_json::Result<Vec<View>> {
+        serde_json::from_str(content)
+    }
+
+    pub fn includes(&self, component: &Component) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&component.kind))
+            && (self.domains.is_empty() || self.domains.contains(&component.kind.domain()))
+            && (self.graphs.is_empty() || self.graphs.contains(&component.graph))
+            && (self.components.is_empty() || self.components.contains(&component.id.value))
+    }
+
+    /// Selects the included components and the relations of included kinds between them.
+    pub fn select(
+        &self,
+        components: &[Component],
+        relations: &[Relation],
+    ) -> (Vec<Component>, Vec<Relation>) {
+        let components = components
+            .iter()
+            .filter(|c| self.includes(c))
+            .cloned()
+            .collect::<Vec<_>>();
+        let relations = relations
+            .iter()
+            .filter(|r| self.relations.is_empty() || self.relations.contains(&r.kind))
+            .filter(|r| components.iter().any(|c| c.id == r.from))
+            .filter(|r| components.iter().any(|c| c.id == r.to))
+            .cloned()
+            .collect::<Vec<_>>();
+        (components, relations)
+    }
+
+    /// Renders the selection, titled with the name, stakeholders and concerns.
+    pub fn visualization(
+        &self,
+        components: &[Component],
+        relations: &[Relation],
+        options: &Options,
+    ) -> Visualization {
+        let (components, relations) = self.select(components, relations);
+        let mut title = vec![self.name.to_string()];
+        if !self.stakeholders.is_empty() {
+            title.push(format!("Stakeholders: {}", self.stakeholders.join(", ")));
+        }
+        if !self.concerns.is_empty() {
+            title.push(format!("Concerns: {}", self.concerns.join(", ")));
+        }
+        let options = Options {
+            title: Some(title.join("\n")),
+            ..options.clone()
+        };
+        visualize(&components, &relations, &options)
+    }
+}
+
+/// A distinct name for files containing each view, based on its name and numbered where names
+/// collide with each other or with the `reserved` names. Views without a name are named `view`.
+pub fn file_names(views: &[View], reserved: &[&str]) -> Vec<String> {
+    let mut taken = reserved
+        .iter()
+        .map(|r| r.to_string())
+        .collect::<HashSet<_>>();
+    views
+        .iter()
+        .map(|v| distinct(&slug(&v.name), "view", &mut taken))
+        .collect()
+}
+
+/// Reads views from statements about them, such as from a `views.sparql` query.
+pub fn from_statements(statements: &[Statement]) -> Result<Vec<View>, String> {
+    let mut views: BTreeMap<&str, View> = BTreeMap::new();
+    for s in statements.iter() {
+        let view = views.entry(s.subject.as_str()).or_default();
+        let error = |message: String| format!("view <{}> in <{}>: {}", s.subject, s.graph, message);
+        let value = match &s.object {
+            rdf::node::Node::UriNode { uri } => uri.to_string().to_string(),
+            rdf::node::Node::LiteralNode { literal, .. } => literal.to_string(),
+            o => return Err(error(format!("unexpected value {:?}", o))),
+        };
+        let property = s.predicate.trim_start_matches(NAMESPACE);
+        match property {
+            RDFS_LABEL => view.name = value,
+            "kind" => view.kinds.push(
+                ComponentKind::from_iri(&value)
+                    .ok_or_else(|| error(format!("unknown kind {}", value)))?,
+            ),
+            "domain" => view.domains.push(
+                ArchitectureDomain::from_name(&value)
+                    .ok_or_else(|| error(format!("unknown domain {}", value)))?,
+            ),
+            "graph" => view.graphs.push(value),
+            "component" => view.components.push(value),
+            "relation" => view.relations.push(
+                RelationKind::from_iri(&value)
+                    .ok_or_else(|| error(format!("unknown relation {}", value)))?,
+            ),
+            "stakeholder" => view.stakeholders.push(value),
+            "concern" => view.concerns.push(value),
+            _ => {}
+        }
+    }
+    Ok(views.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::view::{file_names, from_statements, View, NAMESPACE};
+    use crate::architecture::visualization::Options;
+    use crate::architecture::{fixtures, ArchitectureDomain, ComponentKind, Statement};
+
+    #[test]
+    fn selects_by_kind_and_relation() {
+        let views = View::from_json(
+            r#"[{
+                "name": "Business services",
+                "kinds": ["BusinessService", "Function"],
+                "relations": ["businessServiceProvidesGovernedInterfaceToAccessFunction"],
+                "stakeholders": ["business owners"]
+            }]"#,
+        )
+        .unwrap();
+        let (components, relations) =
+            views[0].select(&fixtures::components(), &fixtures::relations());
+
+        assert_eq!(components.len(), 4);
+        assert_eq!(relations.len(), 2);
+        assert_eq!(file_names(&views, &[]), ["business-services"]);
+        assert!(views[0]
+            .visualization(&components, &relations, &Options::default())
+            .graphviz_content
+            .contains("label=\"Business services\\nStakeholders: business owners\""));
+    }
+
+    #[test]
+    fn reads_views_from_statements() {
+        let statement = |predicate: &str, object: rdf::node::Node| Statement {
+            graph: fixtures::GRAPH.to_string(),
+            subject: "urn:example:view".to_string(),
+            predicate: predicate.to_string(),
+            object,
+        };
+        let views = from_statements(&[
+            statement(
+                "http://www.w3.org/2000/01/rdf-schema#label",
+                rdf::node::Node::LiteralNode {
+                    literal: "application landscape".to_string(),
+                    data_type: None,
+                    language: None,
+                },
+            ),
+            statement(
+                &format!("{}domain", NAMESPACE),
+                rdf::node::Node::LiteralNode {
+                    literal: "application".to_string(),
+                    data_type: None,
+                    language: None,
+                },
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            views,
+            vec![View {
+                name: "application landscape".to_string(),
+                domains: vec![ArchitectureDomain::Application],
+                ..View::default()
+            }]
+        );
+        let (components, _) = views[0].select(&fixtures::components(), &[]);
+        assert!(components
+            .iter()
+            .all(|c| c.kind == ComponentKind::InformationSystemService
+                || c.kind == ComponentKind::LogicalApplicationComponent));
+
+        let unknown = from_statements(&[statement(
+            &format!("{}domain", NAMESPACE),
+            rdf::node::Node::LiteralNode {
+                literal: "aplication".to_string(),
+                data_type: None,
+                language: None,
+            },
+        )]);
+        assert_eq!(
+            unknown,
+            Err(format!(
+                "view <urn:example:view> in <{}>: unknown domain aplication",
+                fixtures::GRAPH
+            ))
+        );
+    }
+
+    #[test]
+    fn names_files_distinctly() {
+        let view = |name: &str| View {
+            name: name.to_string(),
+            ..View::default()
+        };
+        let views = vec![view("Example"), view(""), view("Portal"), view("portal")];
+
+        assert_eq!(
+            file_names(&views, &["example", "diff"]),
+            ["example-2", "view", "portal", "portal-2"]
+        );
+    }
+}
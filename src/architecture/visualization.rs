@@ -0,0 +1,525 @@
+//! Graphviz diagrams of components and the relations between them.
+
+use super::theme::{EdgeStyle, NodeStyle, Theme};
+use super::{ArchitectureDomain, Component, ComponentId, ComponentKind, Relation, RelationKind};
+use crate::graphviz::{self, escape_html, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug)]
+pub struct Visualization {
+    pub graph: graphviz::Graph,
+    pub graphviz_content: String,
+}
+
+impl From<graphviz::Graph> for Visualization {
+    fn from(graph: graphviz::Graph) -> Visualization {
+        Visualization {
+            graphviz_content: graph.to_dot(),
+            graph,
+        }
+    }
+}
+
+impl Visualization {
+    /// Renders SVG with the built-in layout, for when Graphviz is not installed.
+    pub fn to_svg(&self) -> String {
+        graphviz::svg::render(&self.graph)
+    }
+}
+
+/// How to cluster the components in a diagram.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Grouping {
+    None,
+    /// Layers by architecture domain, ordered from business at the top to technology at the
+    /// bottom.
+    Domain,
+    /// Clusters by the named graph that defines each component.
+    Graph,
+    /// Clusters by a property value, such as an owning organization unit. Components without a
+    /// value remain outside of any cluster.
+    Property(HashMap<ComponentId, String>),
+}
+
+/// How to render a diagram.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// A title, which may span multiple lines.
+    pub title: Option<String>,
+    pub theme: Theme,
+    pub grouping: Grouping,
+    /// Whether to add a legend of the component and relation kinds that the diagram shows.
+    pub legend: bool,
+    /// Labels by IRI, such as from the ontology, for kinds in the legend. Kinds without a label
+    /// here have their built-in label.
+    pub labels: HashMap<String, String>,
+    /// A pattern for the URL of the page of each component, such as `components/$SLUG.html`.
+    /// `$IRI`, `$ENCODED_IRI` and `$SLUG` are replaced by the IRI, the IRI encoded for use in a
+    /// query string, and the file name of the component.
+    pub link: Option<String>,
+    /// File names by component, such as from [`file_names`]. Components without one here have
+    /// their [`ComponentId::slug`].
+    ///
+    /// [`file_names`]: super::file_names
+    pub file_names: HashMap<ComponentId, String>,
+    /// Components to emphasize, such as the focus of a diagram.
+    pub highlighted: HashSet<ComponentId>,
+    /// Styles of individual components, on top of the style of their kind.
+    pub node_styles: HashMap<ComponentId, NodeStyle>,
+    /// Styles of individual relations, on top of the style of their kind.
+    pub edge_styles: HashMap<Relation, EdgeStyle>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            title: None,
+            theme: Theme::togaf(),
+            grouping: Grouping::None,
+            legend: false,
+            labels: HashMap::new(),
+            link: None,
+            file_names: HashMap::new(),
+            highlighted: HashSet::new(),
+            node_styles: HashMap::new(),
+            edge_styles: HashMap::new(),
+        }
+    }
+}
+
+pub fn visualization(components: &[Component], relations: &[Relation]) -> Visualization {
+    visualize(components, relations, &Options::default())
+}
+
+pub fn visualize(
+    components: &[Component],
+    relations: &[Relation],
+    options: &Options,
+) -> Visualization {
+    graph(components, relations, options).into()
+}
+
+/// Builds the graph to render, for adding to before rendering.
+pub fn graph(
+    components: &[Component],
+    relations: &[Relation],
+    options: &Options,
+) -> graphviz::Graph {
+    let mut graph = graphviz::Graph {
+        id: "components_relations".to_string(),
+        ..graphviz::Graph::default()
+    };
+    if let Some(title) = &options.title {
+        graphviz::set(&mut graph.attributes, "labelloc", Value::text("t"));
+        graphviz::set(&mut graph.attributes, "label", Value::text(title));
+    }
+    let (ungrouped, groups) = group(components, &options.grouping);
+    graph.nodes = ungrouped.iter().map(|c| node(c, options)).collect();
+    let mut cluster = options.theme.cluster.clone();
+    cluster.shape = None;
+    for (i, (label, members)) in groups.iter().enumerate() {
+        let mut attributes = cluster.attributes();
+        graphviz::set(&mut attributes, "label", Value::text(label));
+        graph.subgraphs.push(graphviz::Subgraph {
+            id: format!("cluster_{}", i),
+            attributes,
+            nodes: members.iter().map(|c| node(c, options)).collect(),
+            subgraphs: vec![],
+        });
+    }
+    let by_id = components
+        .iter()
+        .map(|c| (&c.id, c))
+        .collect::<HashMap<_, _>>();
+    graph.edges = relations.iter().map(|r| edge(r, &by_id, options)).collect();
+    if options.grouping == Grouping::Domain {
+        layer(&mut graph, &groups);
+    }
+    if options.legend {
+        legend(&mut graph, components, relations, options);
+    }
+    graph
+}
+
+/// Splits components into those outside of any group and the groups in order.
+fn group<'a>(
+    components: &'a [Component],
+    grouping: &Grouping,
+) -> (Vec<&'a Component>, Vec<(String, Vec<&'a Component>)>) {
+    match grouping {
+        Grouping::None => (components.iter().collect(), vec![]),
+        Grouping::Domain => {
+            let groups = ArchitectureDomain::ALL
+                .iter()
+                .map(|d| {
+                    let members = components
+                        .iter()
+                        .filter(|c| c.kind.domain() == *d)
+                        .collect::<Vec<_>>();
+                    (d.label().to_string(), members)
+                })
+                .filter(|(_, members)| !members.is_empty())
+                .collect();
+            (vec![], groups)
+        }
+        Grouping::Graph => {
+            let mut groups: BTreeMap<String, Vec<&Component>> = BTreeMap::new();
+            for c in components.iter() {
+                groups.entry(c.graph.to_string()).or_default().push(c);
+            }
+            (vec![], groups.into_iter().collect())
+        }
+        Grouping::Property(values) => {
+            let mut ungrouped = vec![];
+            let mut groups: BTreeMap<String, Vec<&Component>> = BTreeMap::new();
+            for c in components.iter() {
+                match values.get(&c.id) {
+                    Some(v) => groups.entry(v.to_string()).or_default().push(c),
+                    None => ungrouped.push(c),
+                }
+            }
+            (ungrouped, groups.into_iter().collect())
+        }
+    }
+}
+
+/// Orders groups top to bottom by ranking an invisible anchor node between each pair of
+/// consecutive groups, below all members of the one and above all members of the other.
+fn layer(graph: &mut graphviz::Graph, groups: &[(String, Vec<&Component>)]) {
+    graphviz::set(&mut graph.attributes, "newrank", Value::text("true"));
+    for (i, pair) in groups.windows(2).enumerate() {
+        let anchor = format!("layer_{}", i + 1);
+        graph.nodes.push(graphviz::Node {
+            id: anchor.to_string(),
+            attributes: vec![
+                ("shape".to_string(), Value::text("point")),
+                ("style".to_string(), Value::text("invis")),
+            ],
+        });
+        let invisible = || vec![("style".to_string(), Value::text("invis"))];
+        for c in pair[0].1.iter() {
+            graph.edges.push(graphviz::Edge {
+                from: c.id.value.to_string(),
+                to: anchor.to_string(),
+                attributes: invisible(),
+            });
+        }
+        for c in pair[1].1.iter() {
+            graph.edges.push(graphviz::Edge {
+                from: anchor.to_string(),
+                to: c.id.value.to_string(),
+                attributes: invisible(),
+            });
+        }
+    }
+}
+
+/// Adds a cluster with a sample of each component and relation kind in use, in their style.
+fn legend(
+    graph: &mut graphviz::Graph,
+    components: &[Component],
+    relations: &[Relation],
+    options: &Options,
+) {
+    let label = |iri: String, default: &str| {
+        options
+            .labels
+            .get(&iri)
+            .map_or_else(|| default.to_string(), |l| l.to_string())
+    };
+    let mut nodes = vec![];
+    for kind in ComponentKind::ALL.iter() {
+        if components.iter().any(|c| c.kind == *kind) {
+            let mut attributes = options.theme.node_style(*kind).attributes();
+            graphviz::set(
+                &mut attributes,
+                "label",
+                Value::text(&label(kind.iri(), kind.label())),
+            );
+            nodes.push(graphviz::Node {
+                id: format!("legend_{}", kind.name()),
+                attributes,
+            });
+        }
+    }
+    for kind in RelationKind::ALL.iter() {
+        if relations.iter().any(|r| r.kind == *kind) {
+            let ends = [
+                format!("legend_{}_from", kind.name()),
+                format!("legend_{}_to", kind.name()),
+            ];
+            for end in ends.iter() {
+                nodes.push(graphviz::Node {
+                    id: end.to_string(),
+                    attributes: vec![
+                        ("shape".to_string(), Value::text("point")),
+                        ("style".to_string(), Value::text("invis")),
+                    ],
+                });
+            }
+            let mut attributes = options.theme.edge_style(*kind).attributes();
+            graphviz::set(
+                &mut attributes,
+                "label",
+                Value::text(&label(kind.iri(), kind.label())),
+            );
+            graph.edges.push(graphviz::Edge {
+                from: ends[0].to_string(),
+                to: ends[1].to_string(),
+                attributes,
+            });
+        }
+    }
+    if nodes.is_empty() {
+        return;
+    }
+    let mut cluster = options.theme.cluster.clone();
+    cluster.shape = None;
+    let mut attributes = cluster.attributes();
+    graphviz::set(&mut attributes, "label", Value::text("Legend"));
+    graph.subgraphs.push(graphviz::Subgraph {
+        id: "cluster_legend".to_string(),
+        attributes,
+        nodes,
+        subgraphs: vec![],
+    });
+}
+
+fn node(component: &Component, options: &Options) -> graphviz::Node {
+    let mut label = format!(
+        "<B>{}</B><BR/>[{}]",
+        escape_html(&component.label),
+        escape_html(component.kind.label())
+    );
+    if let Some(d) = &component.description {
+        label.push_str("<BR/><BR/>");
+        label.push_str(&escape_html(d));
+    }
+    let mut style = options.theme.node_style(component.kind);
+    if let Some(s) = options.node_styles.get(&component.id) {
+        style.merge(s);
+    }
+    if options.highlighted.contains(&component.id) {
+        style.merge(&options.theme.highlight);
+    }
+    let mut attributes = style.attributes();
+    graphviz::set(&mut attributes, "label", Value::Html(label));
+    let tooltip = component.description.as_ref().unwrap_or(&component.label);
+    graphviz::set(&mut attributes, "tooltip", Value::text(tooltip));
+    link(&mut attributes, &component.id, options);
+    graphviz::Node {
+        id: component.id.value.to_string(),
+        attributes,
+    }
+}
+
+/// Renders a relation, linking to the page of the component it is from.
+pub(super) fn edge(
+    relation: &Relation,
+    components: &HashMap<&ComponentId, &Component>,
+    options: &Options,
+) -> graphviz::Edge {
+    let label = |id: &ComponentId| {
+        components
+            .get(id)
+            .map_or_else(|| id.value.to_string(), |c| c.label.to_string())
+    };
+    let mut style = options.theme.edge_style(relation.kind);
+    if let Some(s) = options.edge_styles.get(relation) {
+        style.merge(s);
+    }
+    let mut attributes = style.attributes();
+    graphviz::set(&mut attributes, "label", Value::text(relation.kind.label()));
+    let tooltip = format!(
+        "{} {} {}",
+        label(&relation.from),
+        relation.kind.label(),
+        label(&relation.to)
+    );
+    graphviz::set(&mut attributes, "tooltip", Value::Text(tooltip));
+    link(&mut attributes, &relation.from, options);
+    graphviz::Edge {
+        from: relation.from.value.to_string(),
+        to: relation.to.value.to_string(),
+        attributes,
+    }
+}
+
+fn link(attributes: &mut graphviz::Attributes, id: &ComponentId, options: &Options) {
+    if let Some(url) = url(id, options) {
+        graphviz::set(attributes, "URL", Value::Text(url));
+        graphviz::set(attributes, "target", Value::text("_top"));
+    }
+}
+
+/// The URL of the page of the component, if the options have a pattern for it.
+pub(super) fn url(id: &ComponentId, options: &Options) -> Option<String> {
+    let pattern = options.link.as_ref()?;
+    let encoded = url::form_urlencoded::byte_serialize(id.value.as_bytes()).collect::<String>();
+    Some(
+        pattern
+            .replace("$ENCODED_IRI", &encoded)
+            .replace("$IRI", &id.value)
+            .replace(
+                "$SLUG",
+                &options
+                    .file_names
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| id.slug()),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::architecture::visualization::{visualization, visualize, Grouping, Options};
+    use crate::architecture::{fixtures, Component, ComponentId, ComponentKind, Relation};
+    use crate::architecture::{view::View, RelationKind};
+    use crate::graphviz::parser;
+    use proptest::prelude::*;
+
+    #[test]
+    fn escapes_labels_and_descriptions() {
+        let mut components = fixtures::components();
+        components[0].label = "R&D <core>".to_string();
+        components[0].description = Some("Input -> \"output\"".to_string());
+        let content = visualization(&components, &fixtures::relations()).graphviz_content;
+
+        assert!(content.contains("<B>R&amp;D &lt;core&gt;</B>"));
+        assert!(content.contains("Input -&gt; &quot;output&quot;"));
+        assert_eq!(parser::parse(&content), Ok(()));
+    }
+
+    #[test]
+    fn layers_domains_in_clusters() {
+        let options = Options {
+            grouping: Grouping::Domain,
+            ..Options::default()
+        };
+        let content =
+            visualize(&fixtures::components(), &fixtures::relations(), &options).graphviz_content;
+
+        assert!(content.contains("subgraph cluster_0 {"));
+        assert!(content.contains("label=\"Business architecture\""));
+        assert!(content.contains("label=\"Application architecture\""));
+        assert!(!content.contains("Data architecture"));
+        assert!(content.contains(&format!(
+            "\"{}\" -> layer_1 [style=\"invis\"]",
+            fixtures::id("architecture-modelling").value
+        )));
+        assert!(content.contains(&format!(
+            "layer_1 -> \"{}\" [style=\"invis\"]",
+            fixtures::id("knowledge-application-service").value
+        )));
+        assert_eq!(parser::parse(&content), Ok(()));
+    }
+
+    #[test]
+    fn clusters_by_property() {
+        let owner = vec![(fixtures::id("knowledge-service"), "R&D".to_string())];
+        let options = Options {
+            grouping: Grouping::Property(owner.into_iter().collect()),
+            ..Options::default()
+        };
+        let content =
+            visualize(&fixtures::components(), &fixtures::relations(), &options).graphviz_content;
+
+        assert_eq!(content.matches("subgraph").count(), 1);
+        assert!(!content.contains("layer_"));
+        assert_eq!(parser::parse(&content), Ok(()));
+    }
+
+    #[test]
+    fn lists_kinds_in_use_in_legend() {
+        let components = fixtures::components();
+        let relations = fixtures::relations();
+        let options = Options {
+            legend: true,
+            labels: vec![(ComponentKind::Process.iri(), "process step".to_string())]
+                .into_iter()
+                .collect(),
+            ..Options::default()
+        };
+        let content = visualize(&components, &relations[..1], &options).graphviz_content;
+
+        assert!(content.contains("subgraph cluster_legend {"));
+        assert!(content.contains("legend_Process [shape=\"cds\""));
+        assert!(content.contains("label=\"process step\"]"));
+        assert!(content.contains("label=\"Function\"]"));
+        assert!(!content.contains("legend_DataEntity"));
+        assert!(content
+            .contains("legend_businessServiceProvidesGovernedInterfaceToAccessFunction_from ->"));
+        assert!(!content.contains("legend_informationSystemServiceRealizesBusinessService"));
+        assert_eq!(parser::parse(&content), Ok(()));
+    }
+
+    #[test]
+    fn links_nodes_and_edges_to_component_pages() {
+        let options = Options {
+            link: Some("components/$SLUG.html?iri=$ENCODED_IRI".to_string()),
+            ..Options::default()
+        };
+        let content =
+            visualize(&fixtures::components(), &fixtures::relations(), &options).graphviz_content;
+
+        assert!(content.contains(
+            "URL=\"components/knowledge-management.html?iri=urn%3Auuid%3A907d6137-14d5-4ef8-93bf-e967bc558fee%23knowledge-management\""
+        ));
+        assert!(content.contains("tooltip=\"Management of models representing knowledge.\""));
+        assert!(content.contains("tooltip=\"knowledge service provides governed interface to access knowledge management\""));
+        assert_eq!(content.matches("URL=").count(), 16);
+        assert_eq!(parser::parse(&content), Ok(()));
+    }
+
+    fn component() -> impl Strategy<Value = Component> {
+        (
+            any::<String>(),
+            any::<String>(),
+            proptest::option::of(any::<String>()),
+            proptest::sample::select(ComponentKind::ALL.to_vec()),
+        )
+            .prop_map(|(id, label, description, kind)| Component {
+                id: ComponentId { value: id },
+                label,
+                description,
+                kind,
+                graph: fixtures::GRAPH.to_string(),
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn renders_parseable_dot(
+            components in proptest::collection::vec(component(), 1..5),
+            kinds in proptest::collection::vec(proptest::sample::select(RelationKind::ALL.to_vec()), 0..5),
+            name in any::<String>(),
+            concerns in proptest::collection::vec(any::<String>(), 0..3),
+            grouping in prop_oneof![Just(Grouping::None), Just(Grouping::Domain), Just(Grouping::Graph)],
+        ) {
+            let relations = kinds
+                .iter()
+                .enumerate()
+                .map(|(i, kind)| Relation {
+                    from: components[i % components.len()].id.clone(),
+                    to: components[(i + 1) % components.len()].id.clone(),
+                    kind: *kind,
+                })
+                .collect::<Vec<_>>();
+            let options = Options {
+                grouping,
+                legend: true,
+                link: Some(name.to_string()),
+                ..Options::default()
+            };
+            let content = visualize(&components, &relations, &options).graphviz_content;
+            prop_assert_eq!(parser::parse(&content), Ok(()), "{}", content);
+
+            let view = View { name, concerns, ..View::default() };
+            let content = view
+                .visualization(&components, &relations, &options)
+                .graphviz_content;
+            prop_assert_eq!(parser::parse(&content), Ok(()), "{}", content);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! A layered layout of directed graphs, as a simpler alternative to the one of `dot`.
+//!
+//! Nodes are ranked top to bottom along the edges, after reversing edges that close cycles
+//! with the edges before them.
+//! Within each rank, nodes are kept together by group and ordered by the mean position of
+//! their neighbours in the rank before. Groups that share ranks are placed side by side. Edges spanning several ranks are not routed around the
+//! nodes in between.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// The horizontal space between nodes in a rank.
+pub const NODE_SEPARATION: f64 = 24.0;
+/// The vertical space between ranks.
+pub const RANK_SEPARATION: f64 = 64.0;
+
+const SWEEPS: usize = 8;
+
+/// A node to lay out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    pub width: f64,
+    pub height: f64,
+    /// The group that the node should be kept together with, such as a cluster.
+    pub group: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug)]
+pub struct Layout {
+    /// The center of each node.
+    pub positions: Vec<Point>,
+    pub ranks: Vec<usize>,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Lays out the nodes, with edges as pairs of node indices, within a box from the origin. Edges
+/// earlier in the list take precedence in ranking, so list the edges that matter most first.
+pub fn layout(nodes: &[Input], edges: &[(usize, usize)]) -> Layout {
+    let ranks = rank(nodes.len(), edges);
+    let order = order(nodes, edges, &ranks);
+
+    // Groups are placed side by side in columns where they share ranks. A group occupies all
+    // ranks from its first to its last, since it is drawn as a box, while the nodes outside of
+    // any group occupy only their own ranks.
+    let mut occupied: BTreeMap<Option<usize>, Vec<bool>> = BTreeMap::new();
+    let mut widths: BTreeMap<Option<usize>, f64> = BTreeMap::new();
+    for (r, rank) in order.iter().enumerate() {
+        for segment in segments(nodes, rank) {
+            let group = nodes[segment[0]].group;
+            occupied
+                .entry(group)
+                .or_insert_with(|| vec![false; order.len()])[r] = true;
+            let width = widths.entry(group).or_insert(0.0);
+            *width = width.max(segment_width(nodes, segment));
+        }
+    }
+    let mut columns: Vec<(Vec<bool>, f64)> = vec![];
+    let mut column_of = HashMap::new();
+    for (group, ranks) in occupied.iter_mut() {
+        if group.is_some() {
+            let first = ranks.iter().position(|o| *o).unwrap_or(0);
+            let last = ranks.iter().rposition(|o| *o).unwrap_or(0);
+            ranks[first..=last].iter_mut().for_each(|o| *o = true);
+        }
+        let free = columns
+            .iter()
+            .position(|(taken, _)| !taken.iter().zip(ranks.iter()).any(|(a, b)| *a && *b));
+        let i = free.unwrap_or_else(|| {
+            columns.push((vec![false; ranks.len()], 0.0));
+            columns.len() - 1
+        });
+        let column = &mut columns[i];
+        column
+            .0
+            .iter_mut()
+            .zip(ranks.iter())
+            .for_each(|(a, b)| *a |= *b);
+        column.1 = column.1.max(widths[group]);
+        column_of.insert(*group, i);
+    }
+    let mut offsets = vec![];
+    let mut width: f64 = 0.0;
+    for (_, column_width) in columns.iter() {
+        if !offsets.is_empty() {
+            width += 2.0 * NODE_SEPARATION;
+        }
+        offsets.push(width);
+        width += column_width;
+    }
+
+    let mut positions = vec![Point { x: 0.0, y: 0.0 }; nodes.len()];
+    let mut y = 0.0;
+    for rank in order.iter() {
+        let height = rank.iter().map(|v| nodes[*v].height).fold(0.0, f64::max);
+        for segment in segments(nodes, rank) {
+            let column = column_of[&nodes[segment[0]].group];
+            let mut x = offsets[column] + (columns[column].1 - segment_width(nodes, segment)) / 2.0;
+            for v in segment.iter() {
+                positions[*v] = Point {
+                    x: x + nodes[*v].width / 2.0,
+                    y: y + height / 2.0,
+                };
+                x += nodes[*v].width + NODE_SEPARATION;
+            }
+        }
+        y += height + RANK_SEPARATION;
+    }
+
+    Layout {
+        positions,
+        ranks,
+        width,
+        height: (y - RANK_SEPARATION).max(0.0),
+    }
+}
+
+/// The runs of nodes in the same group within an ordered rank.
+fn segments<'a>(nodes: &[Input], rank: &'a [usize]) -> Vec<&'a [usize]> {
+    let mut segments = vec![];
+    let mut start = 0;
+    for i in 1..=rank.len() {
+        if i == rank.len() || nodes[rank[i]].group != nodes[rank[start]].group {
+            segments.push(&rank[start..i]);
+            start = i;
+        }
+    }
+    segments
+}
+
+fn segment_width(nodes: &[Input], segment: &[usize]) -> f64 {
+    let widths = segment.iter().map(|v| nodes[*v].width).sum::<f64>();
+    widths + NODE_SEPARATION * (segment.len() as f64 - 1.0)
+}
+
+/// Ranks each node one below the lowest of the nodes it has edges from, counting the edges
+/// that would close cycles in reverse.
+fn rank(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let edges = acyclic(n, edges);
+    let mut outgoing = vec![vec![]; n];
+    let mut incoming = vec![0; n];
+    for (from, to) in edges.iter() {
+        outgoing[*from].push(*to);
+        incoming[*to] += 1;
+    }
+    let mut ranks = vec![0; n];
+    let mut ready = (0..n).filter(|v| incoming[*v] == 0).collect::<Vec<_>>();
+    while let Some(v) = ready.pop() {
+        for w in outgoing[v].iter() {
+            ranks[*w] = ranks[*w].max(ranks[v] + 1);
+            incoming[*w] -= 1;
+            if incoming[*w] == 0 {
+                ready.push(*w);
+            }
+        }
+    }
+    ranks
+}
+
+/// The edges without loops, each reversed if it would close a cycle with the edges before it.
+fn acyclic(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut outgoing = vec![vec![]; n];
+    let mut result = vec![];
+    for (from, to) in edges.iter().filter(|(from, to)| from != to) {
+        let edge = if reaches(&outgoing, *to, *from) {
+            (*to, *from)
+        } else {
+            (*from, *to)
+        };
+        outgoing[edge.0].push(edge.1);
+        result.push(edge);
+    }
+    result
+}
+
+fn reaches(outgoing: &[Vec<usize>], from: usize, to: usize) -> bool {
+    let mut visited = vec![false; outgoing.len()];
+    let mut stack = vec![from];
+    while let Some(v) = stack.pop() {
+        if v == to {
+            return true;
+        }
+        for w in outgoing[v].iter() {
+            if !visited[*w] {
+                visited[*w] = true;
+                stack.push(*w);
+            }
+        }
+    }
+    false
+}
+
+/// Orders the nodes within each rank by group and then by the barycenter of their
+/// neighbours, sweeping down and up the ranks in turn.
+fn order(nodes: &[Input], edges: &[(usize, usize)], ranks: &[usize]) -> Vec<Vec<usize>> {
+    let count = ranks.iter().max().map_or(0, |r| r + 1);
+    let mut order = vec![vec![]; count];
+    for (v, r) in ranks.iter().enumerate() {
+        order[*r].push(v);
+    }
+    let mut neighbours = vec![vec![]; nodes.len()];
+    for (from, to) in edges.iter().filter(|(from, to)| from != to) {
+        neighbours[*from].push(*to);
+        neighbours[*to].push(*from);
+    }
+    let group = |v: usize| nodes[v].group.map_or(0, |g| g + 1);
+
+    let mut positions = vec![0.0; nodes.len()];
+    let update = |positions: &mut Vec<f64>, rank: &[usize]| {
+        for (i, v) in rank.iter().enumerate() {
+            positions[*v] = (i as f64 + 0.5) / rank.len() as f64;
+        }
+    };
+    for rank in order.iter_mut() {
+        rank.sort_by_key(|v| group(*v));
+        update(&mut positions, rank);
+    }
+    for sweep in 0..SWEEPS {
+        let down = sweep % 2 == 0;
+        let sequence = if down {
+            (1..count).collect::<Vec<_>>()
+        } else {
+            (0..count.saturating_sub(1)).rev().collect()
+        };
+        for r in sequence {
+            let barycenters = order[r]
+                .iter()
+                .map(|v| {
+                    let fixed = neighbours[*v]
+                        .iter()
+                        .filter(|w| if down { ranks[**w] < r } else { ranks[**w] > r })
+                        .map(|w| positions[*w])
+                        .collect::<Vec<_>>();
+                    if fixed.is_empty() {
+                        (*v, positions[*v])
+                    } else {
+                        (*v, fixed.iter().sum::<f64>() / fixed.len() as f64)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut sorted = barycenters;
+            sorted.sort_by(|(a, x), (b, y)| {
+                group(*a)
+                    .cmp(&group(*b))
+                    .then(x.partial_cmp(y).unwrap_or(Ordering::Equal))
+            });
+            order[r] = sorted.into_iter().map(|(v, _)| v).collect();
+            update(&mut positions, &order[r]);
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphviz::layout::{layout, Input, NODE_SEPARATION};
+
+    #[test]
+    fn ranks_along_edges_without_overlaps() {
+        let node = |group| Input {
+            width: 60.0,
+            height: 30.0,
+            group,
+        };
+        let nodes = vec![
+            node(None),
+            node(Some(0)),
+            node(Some(1)),
+            node(Some(0)),
+            node(None),
+        ];
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 4), (3, 4), (4, 0), (2, 2)];
+
+        let l = layout(&nodes, &edges);
+
+        assert_eq!(l.ranks, [0, 1, 1, 1, 2]);
+        assert_eq!(layout(&nodes[..2], &[(1, 0), (0, 1)]).ranks, [1, 0]);
+        assert!(l.positions[0].y < l.positions[1].y);
+        assert!(l.positions[1].y < l.positions[4].y);
+        let mut xs = [1, 2, 3]
+            .iter()
+            .map(|v| l.positions[*v].x)
+            .collect::<Vec<_>>();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(xs.windows(2).all(|p| p[1] - p[0] >= 60.0 + NODE_SEPARATION));
+        assert!(
+            (l.positions[1].x - l.positions[3].x).abs()
+                < (l.positions[1].x - l.positions[2].x).abs()
+        );
+        assert!(l
+            .positions
+            .iter()
+            .all(|p| p.x >= 30.0 && p.x <= l.width - 30.0));
+    }
+}
@@ -0,0 +1,245 @@
+//! Graphviz DOT documents, written with each identifier, string and HTML-like label escaped for
+//! its own context.
+
+use crate::xml;
+
+pub mod layout;
+#[cfg(test)]
+pub mod parser;
+pub mod render;
+pub mod svg;
+
+/// An attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Literal text for an escString attribute, such as an edge label. Backslashes show as they
+    /// are and line breaks are kept.
+    Text(String),
+    /// HTML-like markup, of which the text parts should be escaped with [`escape_html`].
+    Html(String),
+}
+
+impl Value {
+    pub fn text(s: &str) -> Value {
+        Value::Text(s.to_string())
+    }
+}
+
+pub type Attributes = Vec<(String, Value)>;
+
+/// Adds or replaces an attribute.
+pub fn set(attributes: &mut Attributes, name: &str, value: Value) {
+    match attributes.iter_mut().find(|(n, _)| n == name) {
+        Some(attribute) => attribute.1 = value,
+        None => attributes.push((name.to_string(), value)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: String,
+    pub attributes: Attributes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub attributes: Attributes,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Subgraph {
+    pub id: String,
+    pub attributes: Attributes,
+    pub nodes: Vec<Node>,
+    pub subgraphs: Vec<Subgraph>,
+}
+
+/// A directed graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Graph {
+    pub id: String,
+    pub attributes: Attributes,
+    pub node_attributes: Attributes,
+    pub edge_attributes: Attributes,
+    pub nodes: Vec<Node>,
+    pub subgraphs: Vec<Subgraph>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    pub fn to_dot(&self) -> String {
+        let mut s = format!("digraph {} {{\n", id(&self.id));
+        write_attributes(&mut s, &self.attributes);
+        if !self.node_attributes.is_empty() {
+            s.push_str("node ");
+            s.push_str(&attribute_list(&self.node_attributes));
+            s.push('\n');
+        }
+        if !self.edge_attributes.is_empty() {
+            s.push_str("edge ");
+            s.push_str(&attribute_list(&self.edge_attributes));
+            s.push('\n');
+        }
+        for n in self.nodes.iter() {
+            write_node(&mut s, n);
+        }
+        for g in self.subgraphs.iter() {
+            write_subgraph(&mut s, g);
+        }
+        for e in self.edges.iter() {
+            s.push_str(&id(&e.from));
+            s.push_str(" -> ");
+            s.push_str(&id(&e.to));
+            if !e.attributes.is_empty() {
+                s.push(' ');
+                s.push_str(&attribute_list(&e.attributes));
+            }
+            s.push('\n');
+        }
+        s.push('}');
+        s
+    }
+}
+
+fn write_attributes(s: &mut String, attributes: &[(String, Value)]) {
+    for (name, value) in attributes.iter() {
+        s.push_str(&id(name));
+        s.push('=');
+        s.push_str(&value_to_dot(value));
+        s.push('\n');
+    }
+}
+
+fn write_node(s: &mut String, node: &Node) {
+    s.push_str(&id(&node.id));
+    if !node.attributes.is_empty() {
+        s.push(' ');
+        s.push_str(&attribute_list(&node.attributes));
+    }
+    s.push('\n');
+}
+
+fn write_subgraph(s: &mut String, subgraph: &Subgraph) {
+    s.push_str("subgraph ");
+    s.push_str(&id(&subgraph.id));
+    s.push_str(" {\n");
+    write_attributes(s, &subgraph.attributes);
+    for n in subgraph.nodes.iter() {
+        write_node(s, n);
+    }
+    for g in subgraph.subgraphs.iter() {
+        write_subgraph(s, g);
+    }
+    s.push_str("}\n");
+}
+
+fn attribute_list(attributes: &[(String, Value)]) -> String {
+    let attributes = attributes
+        .iter()
+        .map(|(name, value)| format!("{}={}", id(name), value_to_dot(value)))
+        .collect::<Vec<_>>();
+    format!("[{}]", attributes.join(","))
+}
+
+fn value_to_dot(value: &Value) -> String {
+    match value {
+        Value::Text(t) => quote_text(t),
+        Value::Html(h) => format!("<{}>", h),
+    }
+}
+
+/// Writes an identifier, quoting it unless it is a plain name.
+pub fn id(s: &str) -> String {
+    let plain = matches!(s.chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !["node", "edge", "graph", "digraph", "subgraph", "strict"]
+            .contains(&s.to_lowercase().as_str());
+    if plain {
+        s.to_string()
+    } else {
+        quote_id(s)
+    }
+}
+
+/// Quotes an identifier. Backslashes are doubled so that none can escape the closing quote.
+pub fn quote_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quotes text for an escString attribute, in which a backslash starts an escape sequence and
+/// `\n` is a centered line break.
+pub fn quote_text(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escapes text for use within HTML-like labels, which Graphviz parses as XML.
+pub fn escape_html(s: &str) -> String {
+    xml::escape(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphviz::render::dot_svg;
+    use crate::graphviz::{escape_html, id, parser, quote_text, Edge, Graph, Node, Value};
+    use proptest::prelude::*;
+
+    #[test]
+    fn escapes_each_context() {
+        assert_eq!(escape_html("R&D <in>"), "R&amp;D &lt;in&gt;");
+        assert_eq!(id("components_relations"), "components_relations");
+        assert_eq!(id("urn:a\\"), "\"urn:a\\\\\"");
+        assert_eq!(id("graph"), "\"graph\"");
+        assert_eq!(
+            quote_text("Input -> \"output\"\n"),
+            "\"Input -> \\\"output\\\"\\n\""
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn writes_parseable_dot(ids in prop::collection::vec(any::<String>(), 1..4), text in any::<String>()) {
+            let graph = Graph {
+                id: ids[0].to_string(),
+                attributes: vec![("label".to_string(), Value::Text(text.to_string()))],
+                nodes: ids
+                    .iter()
+                    .map(|i| Node {
+                        id: i.to_string(),
+                        attributes: vec![(
+                            "label".to_string(),
+                            Value::Html(format!("<B>{}</B>", escape_html(&text))),
+                        )],
+                    })
+                    .collect(),
+                edges: vec![Edge {
+                    from: ids[0].to_string(),
+                    to: ids[ids.len() - 1].to_string(),
+                    attributes: vec![(text.to_string(), Value::Text(text.to_string()))],
+                }],
+                ..Graph::default()
+            };
+            let dot = graph.to_dot();
+            prop_assert!(parser::parse(&dot).is_ok(), "{:?} in {}", parser::parse(&dot), dot);
+            // Graphviz reads the file as C strings, which end at a null character.
+            if !dot.contains('\0') {
+                if let Some(svg) = dot_svg(&dot) {
+                    prop_assert!(svg.is_ok(), "{:?} in {}", svg, dot);
+                }
+            }
+        }
+    }
+}
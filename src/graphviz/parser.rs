@@ -0,0 +1,176 @@
+//! A check of the quoting in generated DOT files, for tests: quoted strings are terminated,
+//! HTML-like labels are well-formed markup with escaped text, and anything else is a symbol or
+//! a plain ID of the grammar at <https://graphviz.org/doc/info/lang.html>. Where Graphviz is
+//! installed, tests also run the files through `dot` itself.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Reads the graph, returning the reason if a string, label or ID is not quoted correctly.
+pub fn parse(content: &str) -> Result<(), String> {
+    let mut open = vec![];
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '{' | '[' => open.push(c),
+            '}' | ']' => match (open.pop(), c) {
+                (Some('{'), '}') | (Some('['), ']') => {}
+                (o, c) => return Err(format!("Closing {} while {:?} is open", c, o)),
+            },
+            '=' | ';' | ',' | ':' => {}
+            '-' if matches!(chars.peek(), Some('>') | Some('-')) => {
+                chars.next();
+            }
+            '"' => quoted(&mut chars)?,
+            '<' => check_markup(&html(&mut chars)?)?,
+            c if c.is_alphanumeric() || "_.-".contains(c) || !c.is_ascii() => {
+                let mut name = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || "_.".contains(c) || !c.is_ascii() {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                check_plain(&name)?;
+            }
+            c => return Err(format!("Unexpected character {:?}", c)),
+        }
+    }
+    match open.pop() {
+        None => Ok(()),
+        Some(o) => Err(format!("Unclosed {}", o)),
+    }
+}
+
+/// Checks that an unquoted ID is a name or a numeral.
+fn check_plain(id: &str) -> Result<(), String> {
+    let name = !id.starts_with(|c: char| c.is_ascii_digit())
+        && id
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || !c.is_ascii());
+    let digits = id.strip_prefix('-').unwrap_or(id);
+    let numeral = digits.matches('.').count() <= 1
+        && digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c == '.' || c.is_ascii_digit());
+    if name || numeral {
+        Ok(())
+    } else {
+        Err(format!("Invalid ID {}", id))
+    }
+}
+
+fn quoted(chars: &mut Peekable<Chars<'_>>) -> Result<(), String> {
+    loop {
+        match chars.next() {
+            None => return Err("Unterminated string".to_string()),
+            Some('"') => return Ok(()),
+            Some('\\') => {
+                chars.next();
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn html(chars: &mut Peekable<Chars<'_>>) -> Result<String, String> {
+    let mut s = String::new();
+    let mut depth = 1;
+    loop {
+        let c = chars
+            .next()
+            .ok_or_else(|| "Unterminated HTML string".to_string())?;
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(s);
+                }
+            }
+            _ => {}
+        }
+        s.push(c);
+    }
+}
+
+/// Checks that HTML-like content consists of balanced elements, entities and text without markup
+/// characters.
+fn check_markup(html: &str) -> Result<(), String> {
+    let mut open: Vec<String> = vec![];
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '<' => {
+                let end = rest
+                    .find('>')
+                    .ok_or_else(|| format!("Unterminated tag in {}", html))?;
+                let tag = &rest[1..end];
+                if let Some(name) = tag.strip_prefix('/') {
+                    match open.pop() {
+                        Some(o) if o == name.trim() => {}
+                        o => return Err(format!("Closing {} while {:?} is open", name, o)),
+                    }
+                } else {
+                    let self_closing = tag.ends_with('/');
+                    let tag = tag.trim_end_matches('/');
+                    let name = tag.split_whitespace().next().unwrap_or("");
+                    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+                        return Err(format!("Invalid tag <{}>", tag));
+                    }
+                    let attributes = &tag[name.len()..];
+                    if attributes.contains('<') || attributes.matches('"').count() % 2 == 1 {
+                        return Err(format!("Invalid attributes in <{}>", tag));
+                    }
+                    if !self_closing {
+                        open.push(name.to_string());
+                    }
+                }
+                rest = &rest[end + 1..];
+            }
+            '&' => {
+                let end = rest
+                    .find(';')
+                    .ok_or_else(|| format!("Unterminated entity in {}", html))?;
+                let entity = &rest[1..end];
+                let valid = match entity.strip_prefix('#') {
+                    Some(n) => match n.strip_prefix('x') {
+                        Some(h) => !h.is_empty() && h.chars().all(|c| c.is_ascii_hexdigit()),
+                        None => !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()),
+                    },
+                    None => ["amp", "lt", "gt", "quot", "apos"].contains(&entity),
+                };
+                if !valid {
+                    return Err(format!("Invalid entity &{};", entity));
+                }
+                rest = &rest[end + 1..];
+            }
+            '>' => return Err(format!("Unescaped > in {}", html)),
+            c if c.is_control() && c != '\n' && c != '\t' && c != '\r' => {
+                return Err(format!("Control character {:?} in {}", c, html))
+            }
+            c => rest = &rest[c.len_utf8()..],
+        }
+    }
+    match open.pop() {
+        None => Ok(()),
+        Some(o) => Err(format!("Unclosed <{}> in {}", o, html)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphviz::parser::parse;
+
+    #[test]
+    fn rejects_unescaped_markup() {
+        assert!(parse("digraph g { a [label=<<B>x</B><BR/>&amp;>] a -> b }").is_ok());
+        assert!(parse("digraph g { a [label=<<B>x & y</B>>] }").is_err());
+        assert!(parse("digraph g { a [label=<<B>x</I>>] }").is_err());
+        assert!(parse("digraph g { \"a\\\" -> b }").is_err());
+        assert!(parse("digraph g { urn:a#b -> b }").is_err());
+        assert!(parse("digraph g { a [width=-1.5] }").is_ok());
+    }
+}
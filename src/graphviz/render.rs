@@ -0,0 +1,223 @@
+//! Rendering of DOT documents with the Graphviz command line tools.
+
+use futures::future;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Svg, Format::Png, Format::Pdf];
+
+    /// The name, as used for the output option and file extension.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Pdf => "pdf",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        Format::ALL.iter().copied().find(|f| f.name() == name)
+    }
+}
+
+/// The Graphviz layout program to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// Hierarchical layout, for directed graphs.
+    Dot,
+    /// Spring model layout.
+    Neato,
+    /// Force-directed layout, which keeps clusters together.
+    Fdp,
+}
+
+impl Engine {
+    pub const ALL: [Engine; 3] = [Engine::Dot, Engine::Neato, Engine::Fdp];
+
+    /// The name, which is also the name of the program.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::Dot => "dot",
+            Engine::Neato => "neato",
+            Engine::Fdp => "fdp",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Engine> {
+        Engine::ALL.iter().copied().find(|e| e.name() == name)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The program could not be started, for example because Graphviz is not installed.
+    Spawn(Engine, io::Error),
+    Io(io::Error),
+    /// The program did not complete within the timeout, and was killed.
+    Timeout(Duration),
+    /// The program failed, with its error output and, if it reported one, the line of the DOT
+    /// document it failed on.
+    Graphviz {
+        status: std::process::ExitStatus,
+        message: String,
+        line: Option<(usize, String)>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Spawn(engine, e) => write!(f, "failed to run {}: {}", engine.name(), e),
+            Error::Io(e) => write!(f, "failed to communicate with Graphviz: {}", e),
+            Error::Timeout(timeout) => write!(f, "Graphviz took longer than {:?}", timeout),
+            Error::Graphviz {
+                status,
+                message,
+                line,
+            } => {
+                write!(f, "Graphviz failed ({}): {}", status, message.trim())?;
+                if let Some((number, content)) = line {
+                    write!(f, "\n{:>4} | {}", number, content)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub engine: Engine,
+    pub format: Format,
+    pub timeout: Duration,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer {
+            engine: Engine::Dot,
+            format: Format::Svg,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Renderer {
+    /// Renders the DOT document to a file, waiting for Graphviz to complete.
+    pub async fn render(&self, dot: &str, path: &Path) -> Result<(), Error> {
+        let mut child = Command::new(self.engine.name())
+            .arg(format!("-T{}", self.format.name()))
+            .arg("-o")
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::Spawn(self.engine, e))?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let write = async move {
+            let result = stdin.write_all(dot.as_bytes()).await;
+            drop(stdin);
+            result
+        };
+        let run = future::join(write, child.wait_with_output());
+        let (written, output) = tokio::time::timeout(self.timeout, run)
+            .await
+            .map_err(|_| Error::Timeout(self.timeout))?;
+        let output = output.map_err(Error::Io)?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).to_string();
+            return Err(Error::Graphviz {
+                status: output.status,
+                line: offending_line(&message, dot),
+                message,
+            });
+        }
+        // Graphviz stops reading its input early only when it fails, so report a failed write
+        // only if it succeeded.
+        written.map_err(Error::Io)
+    }
+}
+
+/// The line that Graphviz reports an error in, such as in `syntax error in line 3 near '->'`.
+fn offending_line(message: &str, dot: &str) -> Option<(usize, String)> {
+    let start = message.find("line ")? + "line ".len();
+    let digits = message[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    let number = digits.parse::<usize>().ok()?;
+    let content = dot.lines().nth(number.checked_sub(1)?)?;
+    Some((number, content.to_string()))
+}
+
+/// Renders the DOT document to SVG with `dot`, or gives none if Graphviz is not installed, so
+/// that tests can compare with Graphviz where it is available.
+#[cfg(test)]
+pub(crate) fn dot_svg(dot: &str) -> Option<Result<String, String>> {
+    use std::io::Write;
+    let mut child = std::process::Command::new("dot")
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let written = child.stdin.take()?.write_all(dot.as_bytes());
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return Some(Err(String::from_utf8_lossy(&output.stderr).to_string()));
+    }
+    Some(
+        written
+            .map_err(|e| e.to_string())
+            .and_then(|_| String::from_utf8(output.stdout).map_err(|e| e.to_string())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphviz::render::{offending_line, Engine, Error, Format};
+    use std::process::Command;
+
+    #[test]
+    fn names_formats_and_engines() {
+        assert_eq!(Format::from_name("pdf"), Some(Format::Pdf));
+        assert_eq!(Format::from_name("jpeg"), None);
+        assert_eq!(Engine::from_name("fdp"), Some(Engine::Fdp));
+    }
+
+    #[test]
+    fn reports_offending_line() {
+        let dot = "digraph g {\na -> b\nb -> -> c\n}";
+        let message = "Error: <stdin>: syntax error in line 3 near '->'\n";
+
+        assert_eq!(
+            offending_line(message, dot),
+            Some((3, "b -> -> c".to_string()))
+        );
+        assert_eq!(offending_line("Warning: no line 9", dot), None);
+        let error = Error::Graphviz {
+            status: Command::new("false").status().unwrap(),
+            message: message.to_string(),
+            line: offending_line(message, dot),
+        };
+        assert!(error.to_string().ends_with("near '->'\n   3 | b -> -> c"));
+    }
+}
@@ -0,0 +1,1024 @@
+//! SVG rendering with the built-in [`layout`](super::layout), for when Graphviz is not
+//! installed.
+//!
+//! This supports the attributes that this tool writes, such as the shapes and styles of the
+//! themes, text and HTML-like labels with bold text and line breaks, clusters, links and
+//! tooltips. Text is measured with an average character width, so labels fit roughly.
+
+use super::layout::{self, Input, Point};
+use super::{Attributes, Graph, Subgraph, Value};
+use crate::xml::escape;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const FONT_SIZE: f64 = 14.0;
+const LINE_HEIGHT: f64 = 17.0;
+/// The average width of a character, relative to the font size.
+const CHARACTER_WIDTH: f64 = 0.6;
+const PADDING: f64 = 8.0;
+const MARGIN: f64 = 40.0;
+const CLUSTER_PADDING: f64 = 12.0;
+const DEFAULT_FONT: &str = "Times,serif";
+
+/// A line of text spans, each bold or not.
+type Line = Vec<(String, bool)>;
+
+struct Item {
+    id: String,
+    attributes: Attributes,
+    cluster: Option<usize>,
+}
+
+struct Cluster<'a> {
+    id: &'a str,
+    attributes: &'a Attributes,
+    parent: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+impl Rect {
+    fn around(center: Point, width: f64, height: f64) -> Rect {
+        Rect {
+            x0: center.x - width / 2.0,
+            y0: center.y - height / 2.0,
+            x1: center.x + width / 2.0,
+            y1: center.y + height / 2.0,
+        }
+    }
+
+    fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
+    fn width(&self) -> f64 {
+        self.x1 - self.x0
+    }
+
+    fn height(&self) -> f64 {
+        self.y1 - self.y0
+    }
+}
+
+/// The position and size of a node or cluster, in points from the top left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<&Rect> for Bounds {
+    fn from(r: &Rect) -> Bounds {
+        Bounds {
+            x: r.x0,
+            y: r.y0,
+            width: r.width(),
+            height: r.height(),
+        }
+    }
+}
+
+/// Where the built-in layout places nodes and clusters, as in the rendered SVG.
+#[derive(Debug)]
+pub struct Placement {
+    /// The bounds of each node by identifier.
+    pub nodes: HashMap<String, Bounds>,
+    /// The bounds of each cluster by subgraph identifier.
+    pub clusters: HashMap<String, Bounds>,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub fn place(graph: &Graph) -> Placement {
+    let arrangement = arrange(graph);
+    Placement {
+        nodes: arrangement
+            .items
+            .iter()
+            .zip(arrangement.boxes.iter())
+            .map(|(item, r)| (item.id.to_string(), r.into()))
+            .collect(),
+        clusters: arrangement
+            .clusters
+            .iter()
+            .zip(arrangement.cluster_boxes.iter())
+            .filter_map(|(c, r)| r.as_ref().map(|r| (c.id.to_string(), r.into())))
+            .collect(),
+        width: arrangement.width,
+        height: arrangement.height,
+    }
+}
+
+struct Arrangement<'a> {
+    items: Vec<Item>,
+    clusters: Vec<Cluster<'a>>,
+    edges: Vec<(usize, usize, Attributes)>,
+    labels: Vec<Vec<Line>>,
+    ranks: Vec<usize>,
+    boxes: Vec<Rect>,
+    cluster_boxes: Vec<Option<Rect>>,
+    title: Vec<Line>,
+    title_on_top: bool,
+    width: f64,
+    height: f64,
+}
+
+fn arrange(graph: &Graph) -> Arrangement<'_> {
+    let mut items = vec![];
+    let mut clusters = vec![];
+    let mut index = HashMap::new();
+    for n in graph.nodes.iter() {
+        add(&mut items, &mut index, &n.id, &n.attributes, None, graph);
+    }
+    for s in graph.subgraphs.iter() {
+        collect(s, None, graph, &mut items, &mut index, &mut clusters);
+    }
+    let mut edges = vec![];
+    for e in graph.edges.iter() {
+        let from = add(&mut items, &mut index, &e.from, &[], None, graph);
+        let to = add(&mut items, &mut index, &e.to, &[], None, graph);
+        let mut attributes = graph.edge_attributes.clone();
+        for (name, value) in e.attributes.iter() {
+            super::set(&mut attributes, name, value.clone());
+        }
+        edges.push((from, to, attributes));
+    }
+
+    let labels = items
+        .iter()
+        .map(|i| lines(get(&i.attributes, "label"), &i.id))
+        .collect::<Vec<_>>();
+    let inputs = items
+        .iter()
+        .zip(labels.iter())
+        .map(|(item, lines)| {
+            let (width, height) = size(&item.attributes, lines);
+            Input {
+                width,
+                height,
+                group: item.cluster,
+            }
+        })
+        .collect::<Vec<_>>();
+    // Invisible edges go first, since they are there to constrain the layout.
+    let (mut pairs, visible): (Vec<_>, Vec<_>) = edges
+        .iter()
+        .partition(|(_, _, attributes)| styles(attributes).contains(&"invis"));
+    pairs.extend(visible);
+    let pairs = pairs.iter().map(|(f, t, _)| (*f, *t)).collect::<Vec<_>>();
+    let layout = layout::layout(&inputs, &pairs);
+
+    let title = match get(&graph.attributes, "label") {
+        Some(v) => lines(Some(v), &graph.id),
+        None => vec![],
+    };
+    let title_height = title.len() as f64 * LINE_HEIGHT;
+    let title_on_top = text(&graph.attributes, "labelloc") == Some("t");
+    let top = MARGIN + if title_on_top { title_height } else { 0.0 };
+    let positions = layout
+        .positions
+        .iter()
+        .map(|p| Point {
+            x: p.x + MARGIN,
+            y: p.y + top,
+        })
+        .collect::<Vec<_>>();
+    let boxes = items
+        .iter()
+        .enumerate()
+        .map(|(i, _)| Rect::around(positions[i], inputs[i].width, inputs[i].height))
+        .collect::<Vec<_>>();
+    let cluster_boxes = cluster_boxes(&clusters, &items, &boxes);
+
+    let content_width = layout.width.max(width_of(&title));
+    let width = content_width + 2.0 * MARGIN;
+    let height = layout.height + title_height + 2.0 * MARGIN;
+
+    Arrangement {
+        items,
+        clusters,
+        edges,
+        labels,
+        ranks: layout.ranks,
+        boxes,
+        cluster_boxes,
+        title,
+        title_on_top,
+        width,
+        height,
+    }
+}
+
+pub fn render(graph: &Graph) -> String {
+    let Arrangement {
+        items,
+        clusters,
+        edges,
+        labels,
+        ranks,
+        boxes,
+        cluster_boxes,
+        title,
+        title_on_top,
+        width,
+        height,
+    } = arrange(graph);
+    let title_height = title.len() as f64 * LINE_HEIGHT;
+    let mut s = String::new();
+    writeln!(
+        s,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         width=\"{w:.0}pt\" height=\"{h:.0}pt\" viewBox=\"0 0 {w:.2} {h:.2}\">",
+        w = width,
+        h = height
+    )
+    .unwrap();
+    writeln!(s, "<title>{}</title>", escape(&graph.id)).unwrap();
+    s.push_str(&markers(&edges));
+    writeln!(
+        s,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        escape(text(&graph.attributes, "bgcolor").unwrap_or("white"))
+    )
+    .unwrap();
+    if !title.is_empty() {
+        let y = if title_on_top {
+            MARGIN / 2.0 + title_height / 2.0
+        } else {
+            height - MARGIN / 2.0 - title_height / 2.0
+        };
+        write_lines(&mut s, &title, &graph.attributes, width / 2.0, y);
+    }
+
+    for (i, cluster) in clusters.iter().enumerate() {
+        let r = match cluster_boxes[i] {
+            Some(r) => r,
+            None => continue,
+        };
+        let style = styles(cluster.attributes);
+        if style.contains(&"invis") {
+            continue;
+        }
+        s.push_str("<g class=\"cluster\">\n");
+        shape(
+            &mut s,
+            "box",
+            &r,
+            &paint(cluster.attributes, &style, "none"),
+            &style,
+        );
+        let label = match get(cluster.attributes, "label") {
+            Some(v) => lines(Some(v), ""),
+            None => vec![],
+        };
+        let y = r.y0 + CLUSTER_PADDING / 2.0 + label.len() as f64 * LINE_HEIGHT / 2.0;
+        write_lines(&mut s, &label, cluster.attributes, (r.x0 + r.x1) / 2.0, y);
+        s.push_str("</g>\n");
+    }
+
+    for (from, to, attributes) in edges.iter() {
+        let style = styles(attributes);
+        if style.contains(&"invis") {
+            continue;
+        }
+        let points = route(&boxes[*from], &boxes[*to], &ranks, *from, *to);
+        open_link(
+            &mut s,
+            "edge",
+            attributes,
+            &format!("{}->{}", items[*from].id, items[*to].id),
+        );
+        let color = text(attributes, "color").unwrap_or("black");
+        let marker = match arrow_head(attributes) {
+            "none" => String::new(),
+            head => format!(" marker-end=\"url(#{})\"", marker_id(head, color)),
+        };
+        writeln!(
+            s,
+            "<path d=\"M{:.2},{:.2} C{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}\" fill=\"none\" {}{}/>",
+            points[0].x,
+            points[0].y,
+            points[1].x,
+            points[1].y,
+            points[2].x,
+            points[2].y,
+            points[3].x,
+            points[3].y,
+            stroke(attributes, &style, color),
+            marker
+        )
+        .unwrap();
+        if let Some(label) = get(attributes, "label") {
+            let label = lines(Some(label), "");
+            let x = (points[0].x + 3.0 * points[1].x + 3.0 * points[2].x + points[3].x) / 8.0;
+            let y = (points[0].y + 3.0 * points[1].y + 3.0 * points[2].y + points[3].y) / 8.0;
+            write_lines(
+                &mut s,
+                &label,
+                attributes,
+                x + width_of(&label) / 2.0 + 4.0,
+                y,
+            );
+        }
+        close_link(&mut s, attributes);
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        let style = styles(&item.attributes);
+        if style.contains(&"invis") {
+            continue;
+        }
+        let name = shape_name(&item.attributes);
+        open_link(&mut s, "node", &item.attributes, &item.id);
+        let default_fill = if name == "point" {
+            text(&item.attributes, "color").unwrap_or("black")
+        } else {
+            "none"
+        };
+        let paint = paint(&item.attributes, &style, default_fill);
+        shape(&mut s, name, &boxes[i], &paint, &style);
+        if name != "point" {
+            let r = &boxes[i];
+            write_lines(
+                &mut s,
+                &labels[i],
+                &item.attributes,
+                (r.x0 + r.x1) / 2.0,
+                (r.y0 + r.y1) / 2.0,
+            );
+        }
+        close_link(&mut s, &item.attributes);
+    }
+    s.push_str("</svg>\n");
+    s
+}
+
+/// Adds a node unless it was added before, with the node defaults of the graph.
+fn add(
+    items: &mut Vec<Item>,
+    index: &mut HashMap<String, usize>,
+    id: &str,
+    attributes: &[(String, Value)],
+    cluster: Option<usize>,
+    graph: &Graph,
+) -> usize {
+    if let Some(i) = index.get(id) {
+        return *i;
+    }
+    let mut merged = graph.node_attributes.clone();
+    for (name, value) in attributes.iter() {
+        super::set(&mut merged, name, value.clone());
+    }
+    items.push(Item {
+        id: id.to_string(),
+        attributes: merged,
+        cluster,
+    });
+    index.insert(id.to_string(), items.len() - 1);
+    items.len() - 1
+}
+
+/// Adds the nodes of a subgraph, and a cluster if its name starts with `cluster`.
+fn collect<'a>(
+    subgraph: &'a Subgraph,
+    parent: Option<usize>,
+    graph: &Graph,
+    items: &mut Vec<Item>,
+    index: &mut HashMap<String, usize>,
+    clusters: &mut Vec<Cluster<'a>>,
+) {
+    let cluster = if subgraph.id.starts_with("cluster") {
+        clusters.push(Cluster {
+            id: &subgraph.id,
+            attributes: &subgraph.attributes,
+            parent,
+        });
+        Some(clusters.len() - 1)
+    } else {
+        parent
+    };
+    for n in subgraph.nodes.iter() {
+        add(items, index, &n.id, &n.attributes, cluster, graph);
+    }
+    for s in subgraph.subgraphs.iter() {
+        collect(s, cluster, graph, items, index, clusters);
+    }
+}
+
+/// The box around the members of each cluster, including its nested clusters and label.
+fn cluster_boxes(clusters: &[Cluster], items: &[Item], boxes: &[Rect]) -> Vec<Option<Rect>> {
+    let mut result: Vec<Option<Rect>> = vec![None; clusters.len()];
+    for i in (0..clusters.len()).rev() {
+        let members = items
+            .iter()
+            .zip(boxes.iter())
+            .filter(|(item, _)| item.cluster == Some(i))
+            .map(|(_, r)| *r);
+        let nested = clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.parent == Some(i))
+            .filter_map(|(j, _)| result[j]);
+        let inner = members.chain(nested).fold(None, |acc: Option<Rect>, r| {
+            Some(acc.map_or(r, |a| a.union(&r)))
+        });
+        let label = match get(clusters[i].attributes, "label") {
+            Some(v) => lines(Some(v), ""),
+            None => vec![],
+        };
+        result[i] = inner.map(|r| Rect {
+            x0: r.x0 - CLUSTER_PADDING,
+            y0: r.y0 - CLUSTER_PADDING - label.len() as f64 * LINE_HEIGHT,
+            x1: r.x1 + CLUSTER_PADDING,
+            y1: r.y1 + CLUSTER_PADDING,
+        });
+    }
+    result
+}
+
+/// The last value of the attribute.
+fn get<'a>(attributes: &'a [(String, Value)], name: &str) -> Option<&'a Value> {
+    attributes
+        .iter()
+        .rev()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v)
+}
+
+fn text<'a>(attributes: &'a [(String, Value)], name: &str) -> Option<&'a str> {
+    match get(attributes, name) {
+        Some(Value::Text(t)) => Some(t),
+        _ => None,
+    }
+}
+
+fn styles(attributes: &[(String, Value)]) -> Vec<&str> {
+    text(attributes, "style")
+        .map(|s| s.split(',').map(str::trim).collect())
+        .unwrap_or_default()
+}
+
+fn shape_name(attributes: &[(String, Value)]) -> &str {
+    text(attributes, "shape").unwrap_or("ellipse")
+}
+
+fn arrow_head(attributes: &[(String, Value)]) -> &str {
+    text(attributes, "arrowhead").unwrap_or("normal")
+}
+
+/// Splits a label into lines: text at its line breaks, as [`quote_text`] writes them for
+/// Graphviz, and HTML-like labels at their line break elements, with bold text and entities.
+/// Without a label, a node shows its identifier, as in Graphviz.
+///
+/// [`quote_text`]: super::quote_text
+fn lines(label: Option<&Value>, id: &str) -> Vec<Line> {
+    match label {
+        Some(Value::Html(h)) => html_lines(h),
+        Some(Value::Text(t)) => text_lines(t),
+        None => text_lines(id),
+    }
+}
+
+fn text_lines(t: &str) -> Vec<Line> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for c in t.chars() {
+        match c {
+            '\n' => lines.push(std::mem::take(&mut line)),
+            '\r' => {}
+            c => line.push(c),
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+        .into_iter()
+        .map(|l| {
+            if l.is_empty() {
+                vec![]
+            } else {
+                vec![(l, false)]
+            }
+        })
+        .collect()
+}
+
+fn html_lines(h: &str) -> Vec<Line> {
+    let mut lines = vec![vec![]];
+    let mut bold = 0;
+    let mut span = String::new();
+    let mut chars = h.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                let tag = chars.by_ref().take_while(|c| *c != '>').collect::<String>();
+                let closing = tag.starts_with('/');
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                if (name == "b" || name == "br") && !span.is_empty() {
+                    let line: &mut Line = lines.last_mut().unwrap();
+                    line.push((std::mem::take(&mut span), bold > 0));
+                }
+                match name.as_str() {
+                    "b" if closing => bold -= 1,
+                    "b" => bold += 1,
+                    "br" => lines.push(vec![]),
+                    _ => {}
+                }
+            }
+            '&' => {
+                let entity = chars.by_ref().take_while(|c| *c != ';').collect::<String>();
+                span.push(decode(&entity));
+            }
+            '\n' | '\t' => span.push(' '),
+            c => span.push(c),
+        }
+    }
+    if !span.is_empty() {
+        lines.last_mut().unwrap().push((span, bold > 0));
+    }
+    lines
+}
+
+fn decode(entity: &str) -> char {
+    let code = if let Some(hex) = entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(decimal) = entity.strip_prefix('#') {
+        decimal.parse().ok()
+    } else {
+        None
+    };
+    match (code, entity) {
+        (Some(code), _) => std::char::from_u32(code).unwrap_or('\u{fffd}'),
+        (None, "amp") => '&',
+        (None, "lt") => '<',
+        (None, "gt") => '>',
+        (None, "quot") => '"',
+        (None, "apos") => '\'',
+        (None, "nbsp") => '\u{a0}',
+        _ => '\u{fffd}',
+    }
+}
+
+fn line_width(line: &Line) -> f64 {
+    line.iter()
+        .map(|(t, bold)| {
+            let factor = if *bold { 1.1 } else { 1.0 };
+            t.chars().count() as f64 * FONT_SIZE * CHARACTER_WIDTH * factor
+        })
+        .sum()
+}
+
+fn width_of(lines: &[Line]) -> f64 {
+    lines.iter().map(line_width).fold(0.0, f64::max)
+}
+
+/// The size of a node to fit its label in its shape.
+fn size(attributes: &[(String, Value)], lines: &[Line]) -> (f64, f64) {
+    let inches = |name| {
+        text(attributes, name)
+            .and_then(|w| w.parse::<f64>().ok())
+            .map(|w| w * 72.0)
+    };
+    let name = shape_name(attributes);
+    if name == "point" {
+        let w = inches("width").unwrap_or(6.0);
+        return (w, w);
+    }
+    let w = width_of(lines);
+    let h = lines.len() as f64 * LINE_HEIGHT;
+    let (w, h) = match name {
+        "plain" => return (w, h),
+        "plaintext" | "none" => (w + 2.0 * PADDING, h + 2.0 * PADDING),
+        "ellipse" | "oval" | "circle" => ((w + 2.0 * PADDING) * 1.42, (h + 2.0 * PADDING) * 1.42),
+        "cds" => (w + 2.0 * PADDING + 16.0, h + 2.0 * PADDING),
+        "note" | "component" => (w + 2.0 * PADDING + 8.0, h + 2.0 * PADDING),
+        "folder" | "box3d" => (w + 2.0 * PADDING, h + 2.0 * PADDING + 6.0),
+        "cylinder" => (w + 2.0 * PADDING, h + 2.0 * PADDING + 12.0),
+        _ => (w + 2.0 * PADDING, h + 2.0 * PADDING),
+    };
+    (
+        w.max(inches("width").unwrap_or(54.0)),
+        h.max(inches("height").unwrap_or(36.0)),
+    )
+}
+
+/// The fill and stroke presentation attributes.
+fn paint(attributes: &[(String, Value)], style: &[&str], default_fill: &str) -> String {
+    let color = text(attributes, "color").unwrap_or("black");
+    let fill = if style.contains(&"filled") {
+        text(attributes, "fillcolor")
+            .or_else(|| text(attributes, "color"))
+            .unwrap_or("lightgrey")
+    } else {
+        default_fill
+    };
+    format!(
+        "fill=\"{}\" {}",
+        escape(fill),
+        stroke(attributes, style, color)
+    )
+}
+
+fn stroke(attributes: &[(String, Value)], style: &[&str], color: &str) -> String {
+    let width = text(attributes, "penwidth")
+        .and_then(|w| w.parse::<f64>().ok())
+        .unwrap_or(if style.contains(&"bold") { 2.0 } else { 1.0 });
+    let mut s = format!("stroke=\"{}\" stroke-width=\"{}\"", escape(color), width);
+    if style.contains(&"dashed") {
+        s.push_str(" stroke-dasharray=\"5,2\"");
+    } else if style.contains(&"dotted") {
+        s.push_str(" stroke-dasharray=\"1,5\"");
+    }
+    s
+}
+
+fn shape(s: &mut String, name: &str, r: &Rect, paint: &str, style: &[&str]) {
+    let polygon = |s: &mut String, points: &[(f64, f64)]| {
+        let points = points
+            .iter()
+            .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+            .collect::<Vec<_>>();
+        writeln!(s, "<polygon points=\"{}\" {}/>", points.join(" "), paint).unwrap();
+    };
+    let (x0, y0, x1, y1) = (r.x0, r.y0, r.x1, r.y1);
+    let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+    match name {
+        "ellipse" | "oval" | "circle" | "point" => writeln!(
+            s,
+            "<ellipse cx=\"{:.2}\" cy=\"{:.2}\" rx=\"{:.2}\" ry=\"{:.2}\" {}/>",
+            cx,
+            cy,
+            r.width() / 2.0,
+            r.height() / 2.0,
+            paint
+        )
+        .unwrap(),
+        "plain" | "plaintext" | "none" => {}
+        "cds" => {
+            let tip = (r.height() / 2.0).min(12.0);
+            polygon(
+                s,
+                &[(x0, y0), (x1 - tip, y0), (x1, cy), (x1 - tip, y1), (x0, y1)],
+            );
+        }
+        "note" => {
+            let fold = 8.0;
+            polygon(
+                s,
+                &[
+                    (x0, y0),
+                    (x1 - fold, y0),
+                    (x1, y0 + fold),
+                    (x1, y1),
+                    (x0, y1),
+                ],
+            );
+            polygon(
+                s,
+                &[(x1 - fold, y0), (x1 - fold, y0 + fold), (x1, y0 + fold)],
+            );
+        }
+        "folder" => {
+            let (tab, height) = (r.width() * 0.3, 6.0);
+            polygon(
+                s,
+                &[
+                    (x0, y0 + height),
+                    (x0 + 2.0, y0),
+                    (x0 + tab, y0),
+                    (x0 + tab + height, y0 + height),
+                    (x1, y0 + height),
+                    (x1, y1),
+                    (x0, y1),
+                ],
+            );
+        }
+        "box3d" => {
+            let d = 4.0;
+            polygon(s, &[(x0, y0 + d), (x1 - d, y0 + d), (x1 - d, y1), (x0, y1)]);
+            polygon(s, &[(x0, y0 + d), (x0 + d, y0), (x1, y0), (x1 - d, y0 + d)]);
+            polygon(s, &[(x1 - d, y0 + d), (x1, y0), (x1, y1 - d), (x1 - d, y1)]);
+        }
+        "cylinder" => {
+            let (rx, ry) = (r.width() / 2.0, (r.height() * 0.1).min(6.0));
+            writeln!(
+                s,
+                "<path d=\"M{x0:.2},{a:.2} A{rx:.2},{ry:.2} 0 0 1 {x1:.2},{a:.2} L{x1:.2},{b:.2} \
+                 A{rx:.2},{ry:.2} 0 0 1 {x0:.2},{b:.2} Z\" {p}/>\n\
+                 <path d=\"M{x0:.2},{a:.2} A{rx:.2},{ry:.2} 0 0 0 {x1:.2},{a:.2}\" {p}/>",
+                x0 = x0,
+                x1 = x1,
+                a = y0 + ry,
+                b = y1 - ry,
+                rx = rx,
+                ry = ry,
+                p = paint
+            )
+            .unwrap();
+        }
+        "component" => {
+            writeln!(
+                s,
+                "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" {}/>",
+                x0,
+                y0,
+                r.width(),
+                r.height(),
+                paint
+            )
+            .unwrap();
+            for y in [y0 + r.height() * 0.25, y0 + r.height() * 0.75 - 4.0].iter() {
+                writeln!(
+                    s,
+                    "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"8\" height=\"4\" {}/>",
+                    x0 - 4.0,
+                    y,
+                    paint
+                )
+                .unwrap();
+            }
+        }
+        _ => {
+            let radius = if style.contains(&"rounded") { 6.0 } else { 0.0 };
+            writeln!(
+                s,
+                "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" rx=\"{}\" {}/>",
+                x0,
+                y0,
+                r.width(),
+                r.height(),
+                radius,
+                paint
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Writes lines of text centered on a point.
+fn write_lines(s: &mut String, lines: &[Line], attributes: &[(String, Value)], x: f64, y: f64) {
+    let font = text(attributes, "fontname").unwrap_or(DEFAULT_FONT);
+    let color = text(attributes, "fontcolor").unwrap_or("black");
+    let top = y - lines.len() as f64 * LINE_HEIGHT / 2.0;
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        write!(
+            s,
+            "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\" font-family=\"{}\" \
+             font-size=\"{}\" fill=\"{}\">",
+            x,
+            top + (i as f64 + 0.75) * LINE_HEIGHT,
+            escape(font),
+            FONT_SIZE,
+            escape(color)
+        )
+        .unwrap();
+        for (t, bold) in line.iter() {
+            if *bold {
+                write!(s, "<tspan font-weight=\"bold\">{}</tspan>", escape(t)).unwrap();
+            } else {
+                s.push_str(&escape(t));
+            }
+        }
+        s.push_str("</text>\n");
+    }
+}
+
+/// Opens a group for an element, within a link if it has a URL.
+fn open_link(s: &mut String, class: &str, attributes: &[(String, Value)], name: &str) {
+    writeln!(s, "<g class=\"{}\">", class).unwrap();
+    let tooltip = text(attributes, "tooltip").unwrap_or(name);
+    writeln!(s, "<title>{}</title>", escape(tooltip)).unwrap();
+    if let Some(url) = text(attributes, "URL").or_else(|| text(attributes, "href")) {
+        write!(s, "<a xlink:href=\"{}\"", escape(url)).unwrap();
+        if let Some(target) = text(attributes, "target") {
+            write!(s, " target=\"{}\"", escape(target)).unwrap();
+        }
+        s.push_str(">\n");
+    }
+}
+
+fn close_link(s: &mut String, attributes: &[(String, Value)]) {
+    if text(attributes, "URL")
+        .or_else(|| text(attributes, "href"))
+        .is_some()
+    {
+        s.push_str("</a>\n");
+    }
+    s.push_str("</g>\n");
+}
+
+/// The points of a cubic Bézier curve between two nodes, leaving and entering on the sides
+/// facing each other.
+fn route(from: &Rect, to: &Rect, ranks: &[usize], f: usize, t: usize) -> [Point; 4] {
+    let p = |x, y| Point { x, y };
+    let (fx, fy) = ((from.x0 + from.x1) / 2.0, (from.y0 + from.y1) / 2.0);
+    let (tx, ty) = ((to.x0 + to.x1) / 2.0, (to.y0 + to.y1) / 2.0);
+    if f == t {
+        return [
+            p(from.x1, fy - 6.0),
+            p(from.x1 + 36.0, fy - 24.0),
+            p(from.x1 + 36.0, fy + 24.0),
+            p(from.x1, fy + 6.0),
+        ];
+    }
+    if ranks[f] == ranks[t] {
+        let (start, end) = if tx > fx {
+            (from.x1, to.x0)
+        } else {
+            (from.x0, to.x1)
+        };
+        let bend = (end - start) / 2.0;
+        return [
+            p(start, fy),
+            p(start + bend, fy),
+            p(end - bend, ty),
+            p(end, ty),
+        ];
+    }
+    let (start, end) = if ranks[t] > ranks[f] {
+        (from.y1, to.y0)
+    } else {
+        (from.y0, to.y1)
+    };
+    let bend = (end - start) / 2.0;
+    [
+        p(fx, start),
+        p(fx, start + bend),
+        p(tx, end - bend),
+        p(tx, end),
+    ]
+}
+
+fn marker_id(head: &str, color: &str) -> String {
+    let color = color
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    format!("arrow_{}_{}", head, color)
+}
+
+/// Definitions of the arrow heads in use, one per shape and color.
+fn markers(edges: &[(usize, usize, Attributes)]) -> String {
+    let mut defined = vec![];
+    let mut s = String::from("<defs>\n");
+    for (_, _, attributes) in edges.iter() {
+        let head = arrow_head(attributes);
+        let color = text(attributes, "color").unwrap_or("black");
+        let id = marker_id(head, color);
+        if head == "none" || defined.contains(&id) {
+            continue;
+        }
+        let (path, width, fill) = match head {
+            "empty" | "onormal" => ("M0,0 L10,3.5 L0,7 Z", 10, "white"),
+            "diamond" => ("M0,3.5 L6,0 L12,3.5 L6,7 Z", 12, color),
+            "odiamond" => ("M0,3.5 L6,0 L12,3.5 L6,7 Z", 12, "white"),
+            "vee" | "open" => ("M0,0 L10,3.5 L0,7 L3,3.5 Z", 10, color),
+            _ => ("M0,0 L10,3.5 L0,7 Z", 10, color),
+        };
+        writeln!(
+            s,
+            "<marker id=\"{}\" markerWidth=\"{w}\" markerHeight=\"7\" refX=\"{w}\" refY=\"3.5\" \
+             orient=\"auto\" markerUnits=\"userSpaceOnUse\">\
+             <path d=\"{}\" fill=\"{}\" stroke=\"{}\"/></marker>",
+            id,
+            path,
+            escape(fill),
+            escape(color),
+            w = width
+        )
+        .unwrap();
+        defined.push(id);
+    }
+    s.push_str("</defs>\n");
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphviz::render::dot_svg;
+    use crate::graphviz::svg::{html_lines, render, text_lines};
+    use crate::graphviz::{Edge, Graph, Node, Subgraph, Value};
+
+    #[test]
+    fn splits_labels_into_lines() {
+        assert_eq!(
+            html_lines("<B>R&amp;D</B><BR/>[&lt;kind&gt;]"),
+            [
+                vec![("R&D".to_string(), true)],
+                vec![("[<kind>]".to_string(), false)]
+            ]
+        );
+        assert_eq!(
+            text_lines("C:\\new\nwithin \\N"),
+            [
+                vec![("C:\\new".to_string(), false)],
+                vec![("within \\N".to_string(), false)]
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_text_literally_like_graphviz() {
+        let graph = Graph {
+            id: "g".to_string(),
+            nodes: vec![Node {
+                id: "a".to_string(),
+                attributes: vec![("label".to_string(), Value::text("C:\\new\nline"))],
+            }],
+            ..Graph::default()
+        };
+        let texts = |svg: &str| {
+            roxmltree::Document::parse(svg)
+                .unwrap()
+                .descendants()
+                .filter(|n| n.has_tag_name("text"))
+                .map(|n| {
+                    n.descendants()
+                        .filter(|t| t.is_text())
+                        .filter_map(|t| t.text())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(texts(&render(&graph)), ["C:\\new", "line"]);
+        if let Some(svg) = dot_svg(&graph.to_dot()) {
+            assert_eq!(texts(&svg.unwrap()), ["C:\\new", "line"]);
+        }
+    }
+
+    #[test]
+    fn renders_well_formed_svg() {
+        let node = |id: &str, label: Value| Node {
+            id: id.to_string(),
+            attributes: vec![
+                ("shape".to_string(), Value::text("cds")),
+                ("label".to_string(), label),
+                ("URL".to_string(), Value::text("a.svg?x=1&y=\"2\"")),
+                ("tooltip".to_string(), Value::text("<tip>")),
+            ],
+        };
+        let graph = Graph {
+            id: "g".to_string(),
+            attributes: vec![("label".to_string(), Value::text("R&D \"title\""))],
+            nodes: vec![node("a", Value::Html("<B>&lt;a&gt;</B><BR/>x".to_string()))],
+            subgraphs: vec![Subgraph {
+                id: "cluster_0".to_string(),
+                attributes: vec![("label".to_string(), Value::text("<cluster>"))],
+                nodes: vec![node("b", Value::text("b\nc"))],
+                subgraphs: vec![],
+            }],
+            edges: vec![
+                Edge {
+                    from: "a".to_string(),
+                    to: "b".to_string(),
+                    attributes: vec![("label".to_string(), Value::text("a & b"))],
+                },
+                Edge {
+                    from: "b".to_string(),
+                    to: "c".to_string(),
+                    attributes: vec![("style".to_string(), Value::text("invis"))],
+                },
+            ],
+            ..Graph::default()
+        };
+
+        let svg = render(&graph);
+
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let groups = |class| {
+            document
+                .descendants()
+                .filter(|n| n.attribute("class") == Some(class))
+                .count()
+        };
+        assert_eq!(groups("node"), 3);
+        assert_eq!(groups("edge"), 1);
+        assert_eq!(groups("cluster"), 1);
+        assert!(svg.contains("&lt;cluster&gt;"));
+        assert!(svg.contains("<tspan font-weight=\"bold\">&lt;a&gt;</tspan>"));
+    }
+}
@@ -71,8 +71,15 @@ pub struct QueryResponseHead {
 #[serde(tag = "type")]
 #[allow(non_camel_case_types)]
 enum QueryResponseValue {
-    uri { value: String },
-    literal { value: String },
+    uri {
+        value: String,
+    },
+    literal {
+        value: String,
+        #[serde(rename = "xml:lang")]
+        language: Option<String>,
+        datatype: Option<String>,
+    },
 }
 
 impl QueryResponseValue {
@@ -81,10 +88,14 @@ impl QueryResponseValue {
             QueryResponseValue::uri { value } => rdf::node::Node::UriNode {
                 uri: rdf::uri::Uri::new(value.to_string()),
             },
-            QueryResponseValue::literal { value } => rdf::node::Node::LiteralNode {
+            QueryResponseValue::literal {
+                value,
+                language,
+                datatype,
+            } => rdf::node::Node::LiteralNode {
                 literal: value.to_string(),
-                data_type: None,
-                language: None,
+                data_type: datatype.as_ref().map(|d| rdf::uri::Uri::new(d.to_string())),
+                language: language.clone(),
             },
         }
     }
@@ -216,9 +227,51 @@ pub async fn get_labels(
 
 #[cfg(test)]
 mod tests {
-    use crate::knowledge::{get_labels, FusekiKnowledgeService, KnowledgeService, Resource};
+    use crate::knowledge::{
+        get_labels, FusekiKnowledgeService, KnowledgeService, QueryResponseValue, Resource,
+    };
     use std::collections::HashSet;
 
+    #[test]
+    fn reads_literal_language_and_datatype() {
+        let values = serde_json::from_str::<Vec<QueryResponseValue>>(
+            r#"[{ "type": "literal", "value": "dienst", "xml:lang": "nl" },
+                { "type": "literal", "value": "1",
+                  "datatype": "http://www.w3.org/2001/XMLSchema#integer" },
+                { "type": "literal", "value": "plain" }]"#,
+        )
+        .unwrap();
+
+        let nodes = values.iter().map(|v| v.to_node()).collect::<Vec<_>>();
+
+        assert_eq!(
+            nodes[0],
+            rdf::node::Node::LiteralNode {
+                literal: "dienst".to_string(),
+                data_type: None,
+                language: Some("nl".to_string()),
+            }
+        );
+        assert_eq!(
+            nodes[1],
+            rdf::node::Node::LiteralNode {
+                literal: "1".to_string(),
+                data_type: Some(rdf::uri::Uri::new(
+                    "http://www.w3.org/2001/XMLSchema#integer".to_string()
+                )),
+                language: None,
+            }
+        );
+        assert!(matches!(
+            &nodes[2],
+            rdf::node::Node::LiteralNode {
+                language: None,
+                data_type: None,
+                ..
+            }
+        ));
+    }
+
     #[tokio::test]
     async fn can_get_labels() {
         let client = reqwest::Client::new();
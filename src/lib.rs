@@ -1,4 +1,7 @@
 pub mod architecture;
 pub mod document;
 pub mod document_workflow;
+pub mod graphviz;
 pub mod knowledge;
+pub mod table;
+pub mod xml;
@@ -1,10 +1,42 @@
 use graph_store::{doc, DataFile, Graph, GraphStore, Resource};
+use std::env;
 use std::fs;
-use std::io::Write;
-use std::process;
-use tooling::architecture::{visualization, ArchitectureService};
+use std::path::Path;
+use std::time::Duration;
+use tooling::architecture::catalog::{self, Artefact};
+use tooling::architecture::cycles::cycles;
+use tooling::architecture::diff::diff;
+use tooling::architecture::explorer::explorer;
+use tooling::architecture::export::archimate::exchange;
+use tooling::architecture::export::mermaid::flowchart;
+use tooling::architecture::export::network::{gexf, graphml, json_graph, Attributes, Value};
+use tooling::architecture::export::plantuml::component_diagram;
+use tooling::architecture::export::structurizr::workspace;
+use tooling::architecture::focus::{focus_each, Direction};
+use tooling::architecture::import::archimate::import;
+use tooling::architecture::lint;
+use tooling::architecture::partition::{index, partition, Partitioning};
+use tooling::architecture::report::Report;
+use tooling::architecture::site::site;
+use tooling::architecture::theme::Theme;
+use tooling::architecture::traceability::traceability;
+use tooling::architecture::view::{self, View};
+use tooling::architecture::visualization::{visualize, Grouping, Options};
+use tooling::architecture::{file_names, ArchitectureService, RelationKind, Visualization};
+use tooling::graphviz::render::{Engine, Format, Renderer};
 use tooling::knowledge::KnowledgeService;
 
+/// The names of the files that the run writes to `doc/` besides those of the views.
+const OUTPUT_NAMES: [&str; 7] = [
+    "archimate",
+    "diff",
+    "example",
+    "explorer",
+    "main",
+    "report",
+    "traceability",
+];
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
@@ -36,6 +68,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             DataFile::unsafe_from_turtle(&fs::read_to_string("architecture.ttl").unwrap()),
         )
         .await;
+    if let Ok(path) = env::var("ARCHIMATE") {
+        let imported = import(
+            &fs::read_to_string(path)?,
+            "http://localhost:3030/architecture/data/archimate.ttl",
+        )?;
+        for unmapped in imported.unmapped.iter() {
+            println!("not imported from ArchiMate: {}", unmapped);
+        }
+        let turtle = imported.to_turtle();
+        fs::write("doc/archimate.ttl", &turtle)?;
+        dataset
+            .import(
+                Graph::Named(Resource::from("archimate.ttl")),
+                DataFile::unsafe_from_turtle(&turtle),
+            )
+            .await;
+    }
 
     let architecture = tooling::architecture::DataBackedArchitectureService {
         dataset: &deprecated_dataset,
@@ -47,30 +96,358 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let relations = architecture.relations().await;
     println!("relations: {:?}", relations);
 
-    let visualization = visualization(components, relations);
+    let traceability = traceability(&components, &relations);
+    fs::write("doc/traceability.csv", traceability.table().to_csv())?;
+    println!("traceability:\n{}", traceability.table().to_markdown());
+
+    let coverage = architecture.coverage().await;
+    println!("coverage:\n{}", coverage.scores_table().to_markdown());
+    println!("missing:\n{}", coverage.missing_table().to_markdown());
+
+    let lint_config = match env::var("LINT_CONFIG") {
+        Ok(path) => lint::Config::parse(&fs::read_to_string(path)?)?,
+        Err(_) => lint::Config::default(),
+    };
+    let findings = lint::lint(
+        &architecture.statements().await,
+        &architecture.classes().await,
+        &lint_config,
+    );
+    for finding in findings.iter() {
+        println!("{}", finding);
+    }
+
+    let cycle_kinds = match env::var("CYCLE_RELATIONS") {
+        Ok(names) => names
+            .split(',')
+            .map(|name| {
+                RelationKind::from_name(name.trim())
+                    .ok_or_else(|| format!("unknown relation {}", name))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Err(_) => RelationKind::ALL.to_vec(),
+    };
+    let cycles = cycles(&relations, &cycle_kinds);
+    for cycle in cycles.iter() {
+        println!("cycle: {}", cycle.describe(&components));
+    }
 
-    let child = process::Command::new("dot")
-        .arg("-Tsvg")
-        .arg("-odoc/example.svg")
-        .stdin(process::Stdio::piped())
-        .spawn()
-        .expect("failed to execute dot");
+    let mut views = architecture.views().await?;
+    let views_content = match env::var("VIEWS") {
+        Ok(path) => Some(fs::read_to_string(path)?),
+        Err(_) => fs::read_to_string("views.json").ok(),
+    };
+    if let Some(content) = views_content {
+        views.extend(View::from_json(&content)?);
+    }
+    let theme = env::var("THEME").unwrap_or_else(|_| "togaf".to_string());
+    let options = Options {
+        theme: match Theme::built_in(&theme) {
+            Some(theme) => theme,
+            None => Theme::from_json(&fs::read_to_string(&theme)?)?,
+        },
+        grouping: match env::var("GROUP_BY").as_deref() {
+            Ok("none") => Grouping::None,
+            Ok("graph") => Grouping::Graph,
+            Ok("domain") | Err(_) => Grouping::Domain,
+            Ok(property) => Grouping::Property(architecture.property_values(property).await?),
+        },
+        legend: env::var("LEGEND").map_or(true, |l| l != "false"),
+        labels: architecture.kind_labels().await,
+        link: env::var("LINK_PATTERN").ok(),
+        file_names: file_names(&components),
+        ..Options::default()
+    };
+    let renderer = renderer()?;
+    let view_names = view::file_names(&views, &OUTPUT_NAMES);
+    for (view, name) in views.iter().zip(view_names.iter()) {
+        let visualization = view.visualization(&components, &relations, &options);
+        render(&renderer, &visualization, &format!("doc/{}", name)).await?;
+    }
+
+    let mut artefacts = Artefact::standard();
+    if let Ok(path) = env::var("CATALOGS") {
+        artefacts.extend(Artefact::from_json(&fs::read_to_string(path)?)?);
+    }
+    fs::create_dir_all("doc/catalogs")?;
+    let artefact_names = catalog::file_names(&artefacts);
+    for (artefact, name) in artefacts.iter().zip(artefact_names.iter()) {
+        let table = artefact.table(&components, &relations);
+        let path = format!("doc/catalogs/{}", name);
+        fs::write(format!("{}.csv", path), table.to_csv())?;
+        fs::write(format!("{}.md", path), table.to_markdown())?;
+        fs::write(
+            format!("{}.html", path),
+            artefact.to_html(&table, "../main.css"),
+        )?;
+    }
+
+    let depth = env::var("FOCUS_DEPTH").map_or(Ok(2), |d| d.parse::<usize>())?;
+    let direction = env::var("FOCUS_DIRECTION").unwrap_or_else(|_| "both".to_string());
+    let direction = Direction::from_name(&direction)
+        .ok_or_else(|| format!("unknown focus direction {}", direction))?;
+    fs::create_dir_all("doc/focus")?;
+    for (name, visualization) in focus_each(&components, &relations, depth, direction, &options) {
+        render(&renderer, &visualization, &format!("doc/focus/{}", name)).await?;
+    }
+
+    let diff = match env::var("BASELINE") {
+        Ok(path) => {
+            let baseline_dataset = graph_store::http::Dataset::get_or_create(
+                &client,
+                url::Url::parse("http://localhost:3030").unwrap(),
+                "baseline",
+            )
+            .await;
+            baseline_dataset
+                .import(
+                    Graph::Named(Resource::from("architecture.ttl")),
+                    DataFile::unsafe_from_turtle(&fs::read_to_string(path)?),
+                )
+                .await;
+            let deprecated_baseline_dataset =
+                knowledge.create_dataset("baseline".to_string()).await;
+            let baseline = tooling::architecture::DataBackedArchitectureService {
+                dataset: &deprecated_baseline_dataset,
+                knowledge: &knowledge,
+            };
+            let diff = diff(
+                &baseline.components().await,
+                &baseline.relations().await,
+                &components,
+                &relations,
+            );
+            println!("changes since the baseline:\n{}", diff.summary());
+            fs::write("doc/diff.txt", diff.summary())?;
+            render(&renderer, &diff.visualization(&options), "doc/diff").await?;
+            Some(diff)
+        }
+        Err(_) => None,
+    };
+
+    let partitioning = match env::var("PARTITION").as_deref() {
+        Ok("domain") => Some(Partitioning::Domain),
+        Ok("connected") => Some(Partitioning::Connected),
+        Ok(other) => match other.parse::<usize>() {
+            Ok(size) => Some(Partitioning::Size(size)),
+            Err(_) => return Err(format!("unknown partitioning {}", other).into()),
+        },
+        Err(_) => None,
+    };
+    if let Some(partitioning) = partitioning {
+        fs::create_dir_all("doc/pages")?;
+        let pages = partition(&components, &relations, partitioning);
+        for page in pages.iter() {
+            let visualization = page.visualization(&pages, &options);
+            render(
+                &renderer,
+                &visualization,
+                &format!("doc/pages/{}", page.file_name()),
+            )
+            .await?;
+        }
+        render(&renderer, &index(&pages, &options), "doc/pages/index").await?;
+    }
 
-    let mut outstdin = child.stdin.expect("failed to obtain stdin");
-    outstdin
-        .write_all(visualization.graphviz_content.as_bytes())
-        .expect("failed to write");
+    let visualization = visualize(&components, &relations, &options);
+    render(&renderer, &visualization, "doc/example").await?;
+
+    let extension = renderer.as_ref().map_or("svg", |r| r.format.name());
+    let mut diagrams = vec![("Architecture".to_string(), "example".to_string())];
+    diagrams.extend(
+        views
+            .iter()
+            .zip(view_names.into_iter())
+            .map(|(v, name)| (v.name.to_string(), name)),
+    );
+    if diff.is_some() {
+        diagrams.push(("Changes since the baseline".to_string(), "diff".to_string()));
+    }
+    let report = Report {
+        title: "Architecture report".to_string(),
+        components: &components,
+        relations: &relations,
+        coverage: &coverage,
+        findings: &findings,
+        diff: diff.as_ref(),
+        diagrams: diagrams
+            .into_iter()
+            .map(|(caption, path)| (caption, format!("{}.{}", path, extension)))
+            .collect(),
+    };
+    fs::write("doc/report.md", report.to_markdown())?;
+    fs::write("doc/report.html", report.to_html())?;
+    fs::copy("main.css", "doc/main.css")?;
+    fs::write(
+        "doc/example.mmd",
+        flowchart(&components, &relations, &options.theme),
+    )?;
+    fs::write(
+        "doc/example.puml",
+        component_diagram(&components, &relations, &options.theme),
+    )?;
+    fs::write(
+        "doc/example.archimate.xml",
+        exchange(&components, &relations, &options),
+    )?;
+    fs::write(
+        "doc/example.dsl",
+        workspace(
+            &components,
+            &relations,
+            env::var("C4_SYSTEM").ok().as_deref(),
+            &options.theme,
+        ),
+    )?;
+    let mut attributes = Attributes::default();
+    for c in components.iter() {
+        let count = |n: usize| Value::Integer(n as i64);
+        let missing = coverage.missing.iter().filter(|m| m.component == c.id);
+        let in_cycles = cycles
+            .iter()
+            .filter(|cycle| cycle.components.contains(&c.id));
+        attributes.components.insert(
+            c.id.clone(),
+            vec![
+                ("missing".to_string(), count(missing.count())),
+                ("cycles".to_string(), count(in_cycles.count())),
+            ],
+        );
+    }
+    fs::write(
+        "doc/example.graphml",
+        graphml(&components, &relations, &attributes)?,
+    )?;
+    fs::write(
+        "doc/example.gexf",
+        gexf(&components, &relations, &attributes)?,
+    )?;
+    fs::write(
+        "doc/example.json",
+        json_graph(&components, &relations, &attributes)?,
+    )?;
+    fs::write(
+        "doc/explorer.html",
+        explorer(&components, &relations, &options),
+    )?;
+
+    let documents = env::var("DOCUMENTS")
+        .unwrap_or_else(|_| "test.md".to_string())
+        .split(',')
+        .map(|path| Ok((path.to_string(), fs::read_to_string(path)?)))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    let site = site(&components, &relations, &documents, &options);
+    for page in site.pages.iter() {
+        let path = Path::new("doc/site").join(&page.path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, &page.content)?;
+    }
+    fs::copy("main.css", "doc/site/main.css")?;
+    // Component pages embed their diagrams as SVG, whatever the format of the others.
+    let svg_renderer = renderer.clone().map(|r| Renderer {
+        format: Format::Svg,
+        ..r
+    });
+    for (path, visualization) in site.diagrams.iter() {
+        render(&svg_renderer, visualization, &format!("doc/site/{}", path)).await?;
+    }
 
     println!("graph: {:?}", visualization);
 
     let ids = architecture.component_ids().await;
     println!("ids: {:?}", ids);
 
-    let descriptions = ids.iter().map(|id| architecture.describe(id));
-    let foo = futures::future::join_all(descriptions).await;
-    println!("descriptions: {:?}", foo);
+    if env::var("RAW_DESCRIPTIONS").is_ok() {
+        let descriptions = ids.iter().map(|id| architecture.describe_graph(id));
+        let foo = futures::future::join_all(descriptions).await;
+        println!("descriptions: {:?}", foo);
+    } else {
+        let descriptions = ids.iter().map(|id| architecture.describe(id));
+        for description in futures::future::join_all(descriptions).await {
+            let label = description.labels.first().map(|l| l.value.as_str());
+            println!(
+                "{} <{}>\n{}",
+                label.unwrap_or(""),
+                description.id.value,
+                description.table().to_markdown()
+            );
+        }
+    }
 
     doc::export_to_html(&dataset).await;
 
+    if !cycles.is_empty() && env::var("FAIL_ON_CYCLES").is_ok() {
+        return Err(format!("{} cycles found", cycles.len()).into());
+    }
+    if lint::has_errors(&findings) {
+        return Err("lint errors found".into());
+    }
+    if let Ok(minimum) = env::var("MINIMUM_COVERAGE") {
+        let minimum = minimum.parse::<f64>()?;
+        if !coverage.meets(minimum) {
+            return Err(format!("coverage below the minimum of {}%", minimum).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// The Graphviz renderer configured by `ENGINE`, `FORMAT` and `RENDER_TIMEOUT` in seconds, or
+/// none for the built-in renderer. `RENDERER` selects `dot`, `builtin` or by default `auto`,
+/// which is Graphviz when the engine is on the path.
+fn renderer() -> Result<Option<Renderer>, Box<dyn std::error::Error>> {
+    let mut renderer = Renderer::default();
+    if let Ok(engine) = env::var("ENGINE") {
+        renderer.engine =
+            Engine::from_name(&engine).ok_or_else(|| format!("unknown engine {}", engine))?;
+    }
+    if let Ok(format) = env::var("FORMAT") {
+        renderer.format =
+            Format::from_name(&format).ok_or_else(|| format!("unknown format {}", format))?;
+    }
+    if let Ok(timeout) = env::var("RENDER_TIMEOUT") {
+        renderer.timeout = Duration::from_secs(timeout.parse()?);
+    }
+    let graphviz = match env::var("RENDERER").as_deref() {
+        Ok("dot") => true,
+        Ok("builtin") => false,
+        Ok("auto") | Err(_) => on_path(renderer.engine.name()),
+        Ok(other) => return Err(format!("unknown renderer {}", other).into()),
+    };
+    if graphviz {
+        Ok(Some(renderer))
+    } else if renderer.format == Format::Svg {
+        Ok(None)
+    } else {
+        Err(format!(
+            "the built-in renderer only writes svg, not {}",
+            renderer.format.name()
+        )
+        .into())
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH").map_or(false, |paths| {
+        env::split_paths(&paths).any(|dir| dir.join(program).is_file())
+    })
+}
+
+/// Renders to the path with the extension of the format added.
+async fn render(
+    renderer: &Option<Renderer>,
+    visualization: &Visualization,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match renderer {
+        Some(renderer) => {
+            let path = format!("{}.{}", path, renderer.format.name());
+            renderer
+                .render(&visualization.graphviz_content, Path::new(&path))
+                .await
+                .map_err(|e| format!("failed to render {}: {}", path, e))?;
+        }
+        None => fs::write(format!("{}.svg", path), visualization.to_svg())?,
+    }
     Ok(())
 }
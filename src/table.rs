@@ -0,0 +1,109 @@
+use crate::xml;
+
+/// Tabular output, such as catalogs and traceability views.
+#[derive(Debug, PartialEq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Renders as RFC 4180 comma-separated values.
+    pub fn to_csv(&self) -> String {
+        let mut s = String::new();
+        for row in std::iter::once(&self.header).chain(self.rows.iter()) {
+            let fields = row.iter().map(|f| csv_field(f)).collect::<Vec<_>>();
+            s.push_str(&fields.join(","));
+            s.push_str("\r\n");
+        }
+        s
+    }
+
+    /// Renders as a GitHub Flavored Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let mut s = markdown_row(&self.header);
+        s.push_str(&markdown_row(
+            &self
+                .header
+                .iter()
+                .map(|_| "---".to_string())
+                .collect::<Vec<_>>(),
+        ));
+        for row in self.rows.iter() {
+            s.push_str(&markdown_row(row));
+        }
+        s
+    }
+
+    /// Renders as an HTML table, with the header in its head.
+    pub fn to_html(&self) -> String {
+        let row = |cells: &[String], tag: &str| {
+            let cells = cells
+                .iter()
+                .map(|c| format!("<{}>{}</{}>", tag, xml::escape(c), tag))
+                .collect::<String>();
+            format!("<tr>{}</tr>\n", cells)
+        };
+        let mut s = String::from("<table>\n<thead>\n");
+        s.push_str(&row(&self.header, "th"));
+        s.push_str("</thead>\n<tbody>\n");
+        for r in self.rows.iter() {
+            s.push_str(&row(r, "td"));
+        }
+        s.push_str("</tbody>\n</table>\n");
+        s
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\r', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn markdown_row(cells: &[String]) -> String {
+    let cells = cells
+        .iter()
+        .map(|c| c.replace('|', "\\|").replace('\n', " "))
+        .collect::<Vec<_>>();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::Table;
+
+    fn table() -> Table {
+        Table {
+            header: vec!["name".to_string(), "note".to_string()],
+            rows: vec![vec!["R&D".to_string(), "a, \"b\" | c".to_string()]],
+        }
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        assert_eq!(
+            "name,note\r\nR&D,\"a, \"\"b\"\" | c\"\r\n",
+            table().to_csv()
+        );
+    }
+
+    #[test]
+    fn escapes_markdown_pipes() {
+        assert_eq!(
+            "| name | note |\n| --- | --- |\n| R&D | a, \"b\" \\| c |\n",
+            table().to_markdown()
+        );
+    }
+
+    #[test]
+    fn escapes_html_cells() {
+        assert_eq!(
+            "<table>\n<thead>\n<tr><th>name</th><th>note</th></tr>\n</thead>\n<tbody>\n\
+             <tr><td>R&amp;D</td><td>a, &quot;b&quot; | c</td></tr>\n</tbody>\n</table>\n",
+            table().to_html()
+        );
+    }
+}
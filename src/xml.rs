@@ -0,0 +1,25 @@
+//! Helpers for writing XML.
+
+/// Escapes text for use in XML attribute values and in content where it shows on one line.
+/// Line breaks and tabs become spaces, and other control characters, which XML 1.0 does not
+/// allow, are left out.
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' | '\t' => escaped.push(' '),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes text for use in XML content, keeping its line breaks.
+pub fn escape_content(s: &str) -> String {
+    s.split('\n').map(escape).collect::<Vec<_>>().join("\n")
+}